@@ -0,0 +1,290 @@
+use crate::input_error::ErrorKind;
+use crate::input_error::InputError;
+use crate::Answer;
+use std::convert::TryFrom;
+
+// More decimal places than this cannot be rescaled within an i128.
+pub const MAX_SCALE: u32 = 18;
+
+// A fixed-point decimal: `units` counts steps of 10^-scale, so 12.34 is 1234 at scale 2.
+// Amounts are equal and hash alike when their values are, so 12 equals 12.00.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Amount {
+    units: i64,
+    scale: u32,
+}
+
+impl Amount {
+    pub fn new(units: i64, scale: u32) -> Amount {
+        return Amount { units: units, scale: scale };
+    }
+
+    pub fn whole(value: i64) -> Amount {
+        return Amount::new(value, 0);
+    }
+
+    pub fn units(&self) -> i64 {
+        return self.units;
+    }
+
+    pub fn scale(&self) -> u32 {
+        return self.scale;
+    }
+
+    // The same value counted in steps of 10^-scale, or None when it has more decimal places
+    // than that or is too large.
+    pub fn units_at_scale(&self, scale: u32) -> Option<i128> {
+        if scale >= self.scale {
+            return 10i128.checked_pow(scale - self.scale).and_then(|factor| (self.units as i128).checked_mul(factor));
+        }
+
+        let factor = 10i128.checked_pow(self.scale - scale)?;
+        if self.units as i128 % factor != 0 {
+            return None;
+        }
+
+        return Some(self.units as i128 / factor);
+    }
+
+    pub fn rescale(&self, scale: u32) -> Option<Amount> {
+        let units = self.units_at_scale(scale).and_then(|u| i64::try_from(u).ok())?;
+
+        return Some(Amount::new(units, scale));
+    }
+
+    // The fewest decimal places that hold the value exactly, e.g. 12.50 as (125, 1).
+    fn normalized(&self) -> (i64, u32) {
+        let (mut units, mut scale) = (self.units, self.scale);
+        while scale > 0 && units % 10 == 0 {
+            units /= 10;
+            scale -= 1;
+        }
+
+        return (units, scale);
+    }
+}
+
+impl PartialEq for Amount {
+    fn eq(&self, other: &Amount) -> bool {
+        return self.normalized() == other.normalized();
+    }
+}
+
+impl Eq for Amount {}
+
+impl std::hash::Hash for Amount {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.normalized().hash(state);
+    }
+}
+
+impl From<i64> for Amount {
+    fn from(value: i64) -> Amount {
+        return Amount::whole(value);
+    }
+}
+
+// Accepts an optional sign, whole digits and optional decimal digits, e.g. 12, -7 and +12.34.
+impl std::str::FromStr for Amount {
+    type Err = InputError;
+
+    fn from_str(text: &str) -> Result<Amount, InputError> {
+        let malformed = |column: usize| {
+            InputError::new(ErrorKind::MalformedExpense, "expected an amount such as 12 or -12.34").with_text(text).at_column(column)
+        };
+
+        let unsigned = text.strip_prefix(['+', '-']).unwrap_or(text);
+        let sign_width = text.len() - unsigned.len();
+        let (whole, fraction) = match unsigned.split_once('.') {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (unsigned, None),
+        };
+
+        if let Some(i) = whole.find(|c: char| !c.is_ascii_digit()) {
+            return Err(malformed(sign_width + whole[..i].chars().count() + 1));
+        }
+        if whole.is_empty() {
+            return Err(malformed(sign_width + 1));
+        }
+
+        let fraction = fraction.unwrap_or("");
+        let fraction_column = sign_width + whole.len() + 2;
+        if let Some(i) = fraction.find(|c: char| !c.is_ascii_digit()) {
+            return Err(malformed(fraction_column + fraction[..i].chars().count()));
+        }
+        if unsigned.ends_with('.') {
+            return Err(malformed(fraction_column));
+        }
+        if fraction.len() > MAX_SCALE as usize {
+            return Err(
+                InputError::new(ErrorKind::MalformedExpense, format!("amounts may have at most {} decimal places", MAX_SCALE))
+                    .with_text(text)
+                    .at_column(fraction_column + MAX_SCALE as usize)
+            );
+        }
+
+        let digits = format!("{}{}{}", if text.starts_with('-') { "-" } else { "" }, whole, fraction);
+        let units = digits.parse::<i64>().map_err(|e| malformed(1).with_source(e))?;
+
+        return Ok(Amount::new(units, fraction.len() as u32));
+    }
+}
+
+impl std::fmt::Display for Amount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "{}", fixed_point(self.units.to_string(), self.scale));
+    }
+}
+
+// Inserts the decimal point `scale` digits from the right of a signed integer's digits.
+fn fixed_point(digits: String, scale: u32) -> String {
+    if scale == 0 {
+        return digits;
+    }
+
+    let (sign, digits) = match digits.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", digits.as_str()),
+    };
+    let padded = format!("{:0>width$}", digits, width = scale as usize + 1);
+    let (whole, fraction) = padded.split_at(padded.len() - scale as usize);
+
+    return format!("{}{}.{}", sign, whole, fraction);
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum ProductUnits {
+    Checked(i128),
+    Big(num_bigint::BigInt),
+}
+
+// The product of several amounts, whose scale is the sum of theirs. It is multiplied with checked
+// i128 arithmetic and moves to a BigInt once a step would overflow.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Product {
+    units: ProductUnits,
+    scale: u32,
+}
+
+impl Product {
+    pub fn of(amounts: &[Amount]) -> Product {
+        let mut units = ProductUnits::Checked(1);
+        for amount in amounts {
+            units = match units {
+                ProductUnits::Checked(product) => match product.checked_mul(amount.units as i128) {
+                    Some(next) => ProductUnits::Checked(next),
+                    None => ProductUnits::Big(num_bigint::BigInt::from(product) * amount.units),
+                },
+                ProductUnits::Big(product) => ProductUnits::Big(product * amount.units),
+            };
+        }
+
+        return Product { units: units, scale: amounts.iter().map(|a| a.scale).sum() };
+    }
+
+    pub fn scale(&self) -> u32 {
+        return self.scale;
+    }
+
+    // Whether the product outgrew i128 and is held as a BigInt.
+    pub fn is_big(&self) -> bool {
+        return matches!(self.units, ProductUnits::Big(_));
+    }
+
+    pub fn units(&self) -> num_bigint::BigInt {
+        return match &self.units {
+            ProductUnits::Checked(product) => num_bigint::BigInt::from(*product),
+            ProductUnits::Big(product) => product.clone(),
+        };
+    }
+}
+
+impl std::fmt::Display for Product {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let digits = match &self.units {
+            ProductUnits::Checked(product) => product.to_string(),
+            ProductUnits::Big(product) => product.to_string(),
+        };
+
+        return write!(f, "{}", fixed_point(digits, self.scale));
+    }
+}
+
+// Whole products keep the integer answer types, so they compare and print as before; products
+// with decimal places are answered as their text.
+impl From<Product> for Answer {
+    fn from(product: Product) -> Answer {
+        if product.scale > 0 {
+            return Answer::Text(product.to_string());
+        }
+
+        return match product.units {
+            ProductUnits::Checked(v) if v >= 0 => Answer::Unsigned(v as u128),
+            ProductUnits::Checked(v) => match i64::try_from(v) {
+                Ok(v) => Answer::Signed(v),
+                Err(_) => Answer::BigInteger(num_bigint::BigInt::from(v)),
+            },
+            ProductUnits::Big(v) => Answer::BigInteger(v),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Amount;
+    use super::Product;
+    use crate::Answer;
+
+    #[test]
+    fn parses_signed_and_decimal_amounts() {
+        assert_eq!(Amount::new(1234, 2), "12.34".parse::<Amount>().unwrap());
+        assert_eq!(Amount::new(-705, 1), "-70.5".parse::<Amount>().unwrap());
+        assert_eq!(Amount::whole(12), "+12".parse::<Amount>().unwrap());
+        assert_eq!(Amount::new(-5, 2), "-0.05".parse::<Amount>().unwrap());
+        assert_eq!("-0.05", Amount::new(-5, 2).to_string());
+        assert_eq!("12.340", Amount::new(12340, 3).to_string());
+
+        for (text, column) in &[("12.3x", 5), ("1,5", 2), ("-", 2), ("12.", 4), (".5", 1), ("--1", 2), ("", 1)] {
+            assert_eq!(Some(*column), text.parse::<Amount>().unwrap_err().column(), "{}", text);
+        }
+        assert!("99999999999999999999".parse::<Amount>().is_err());
+        assert!("0.1234567890123456789".parse::<Amount>().is_err());
+    }
+
+    #[test]
+    fn rescales_only_when_exact() {
+        assert_eq!(Some(120000), Amount::whole(12).units_at_scale(4));
+        assert_eq!(Some(12), Amount::new(1200, 2).units_at_scale(0));
+        assert_eq!(None, Amount::new(1234, 2).units_at_scale(1));
+        assert_eq!(Some((-500, 2)), Amount::whole(-5).rescale(2).map(|a| (a.units(), a.scale())));
+        assert_eq!(None, Amount::whole(i64::MAX).rescale(1));
+    }
+
+    #[test]
+    fn equal_values_are_equal_at_any_scale() {
+        assert_eq!(Amount::whole(12), Amount::new(1200, 2));
+        assert_eq!(Amount::new(-125, 1), "-12.500".parse::<Amount>().unwrap());
+        assert_ne!(Amount::whole(12), Amount::new(12, 1));
+        assert_ne!(Amount::whole(0), Amount::new(1, 18));
+
+        let amounts: std::collections::HashSet<Amount> = [Amount::whole(12), Amount::new(1200, 2), Amount::new(120, 1)].iter().cloned().collect();
+        assert_eq!(1, amounts.len());
+    }
+
+    #[test]
+    fn products_are_checked_and_fall_back_to_big_integers() {
+        let small = Product::of(&[Amount::whole(1721), Amount::whole(299)]);
+        assert_eq!(Answer::Unsigned(514579), Answer::from(small.clone()));
+        assert!(!small.is_big());
+
+        let big = Product::of(&[Amount::whole(i64::MAX), Amount::whole(i64::MAX), Amount::whole(-3)]);
+        assert!(big.is_big());
+        assert_eq!(num_bigint::BigInt::from(i64::MAX) * i64::MAX * -3, big.units());
+        assert_eq!("-255211775190703847542190723352697503747", big.to_string());
+
+        assert_eq!(Answer::Signed(-6), Answer::from(Product::of(&[Amount::whole(-2), Amount::whole(3)])));
+        assert_eq!(Answer::from("-30.850"), Answer::from(Product::of(&[Amount::new(-1234, 2), Amount::new(25, 1)])));
+        assert_eq!("1", Product::of(&[]).to_string());
+    }
+}
@@ -0,0 +1,81 @@
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+    Signed(i64),
+    Unsigned(u128),
+    BigInteger(num_bigint::BigInt),
+    Text(String),
+}
+
+impl std::fmt::Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            Answer::Signed(v) => write!(f, "{}", v),
+            Answer::Unsigned(v) => write!(f, "{}", v),
+            Answer::BigInteger(v) => write!(f, "{}", v),
+            Answer::Text(v) => write!(f, "{}", v),
+        };
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(value: i64) -> Answer {
+        return Answer::Signed(value);
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Answer {
+        return Answer::Unsigned(value as u128);
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(value: u64) -> Answer {
+        return Answer::Unsigned(value as u128);
+    }
+}
+
+impl From<u128> for Answer {
+    fn from(value: u128) -> Answer {
+        return Answer::Unsigned(value);
+    }
+}
+
+impl From<num_bigint::BigInt> for Answer {
+    fn from(value: num_bigint::BigInt) -> Answer {
+        return Answer::BigInteger(value);
+    }
+}
+
+impl From<num_bigint::BigUint> for Answer {
+    fn from(value: num_bigint::BigUint) -> Answer {
+        return Answer::BigInteger(num_bigint::BigInt::from(value));
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Answer {
+        return Answer::Text(value);
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Answer {
+        return Answer::Text(value.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Answer;
+
+    #[test]
+    fn display_keeps_sign_and_width() {
+        assert_eq!("-42", Answer::from(-42i64).to_string());
+        assert_eq!("340282366920938463463374607431768211455", Answer::from(u128::MAX).to_string());
+
+        let big = num_bigint::BigInt::from(u128::MAX) * 10;
+        assert_eq!("3402823669209384634633746074317682114550", Answer::from(big).to_string());
+        assert_eq!("shiny gold", Answer::from("shiny gold").to_string());
+    }
+}
@@ -0,0 +1,105 @@
+use crate::input_error::ErrorKind;
+use crate::input_error::InputError;
+use crate::input::PuzzleInput;
+use crate::DirectedGraph;
+
+// A luggage rule: a bag colour and the count of each colour it directly contains.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BagRule {
+    pub bag: String,
+    pub contents: Vec<(String, i64)>,
+}
+
+// Each rule becomes a node with an edge, weighted by count, to every bag it directly contains.
+pub fn build_graph(rules: &Vec<String>) -> Result<DirectedGraph, InputError> {
+    let mut graph = DirectedGraph::new();
+    for (i, line) in rules.iter().enumerate() {
+        let rule = line.parse::<BagRule>().map_err(|e| e.with_line_text(line).at_line(i + 1))?;
+
+        graph.add_node_if_not_exists(rule.bag.clone());
+        for (bag, count) in rule.contents {
+            graph.add_node_if_not_exists(bag.clone());
+            graph.add_edge_by_names(&rule.bag, &bag, count);
+        }
+    }
+
+    return Ok(graph);
+}
+
+// Rules look like "light red bags contain 1 bright white bag, 2 muted yellow bags." or
+// "faded blue bags contain no other bags."
+impl std::str::FromStr for BagRule {
+    type Err = InputError;
+
+    fn from_str(line: &str) -> Result<BagRule, InputError> {
+        let separator = " bags contain ";
+        let (from_node, contents) = match line.split_once(separator) {
+            Some(parts) => parts,
+            None => return Err(malformed_rule(line, 1, "expected \"<colour> bags contain ...\"")),
+        };
+
+        let mut column = from_node.chars().count() + separator.len() + 1;
+        let contents = match contents.strip_suffix('.') {
+            Some(c) => c,
+            None => return Err(malformed_rule("", column + contents.chars().count(), "expected the rule to end with a full stop")),
+        };
+
+        let mut to_nodes = Vec::new();
+        if contents == "no other bags" {
+            return Ok(BagRule { bag: from_node.to_string(), contents: to_nodes });
+        }
+
+        for item in contents.split(", ") {
+            let (count, rest) = match item.split_once(' ') {
+                Some(parts) => parts,
+                None => return Err(malformed_rule(item, column, "expected a count followed by a bag colour")),
+            };
+            let weight = count.parse::<i64>()
+                .map_err(|e| malformed_rule(count, column, "expected a bag count").with_source(e))?;
+            if weight < 1 {
+                return Err(malformed_rule(count, column, "expected a bag count of at least 1"));
+            }
+            let to_node = match rest.strip_suffix(" bags").or_else(|| rest.strip_suffix(" bag")) {
+                Some(n) => n,
+                None => return Err(malformed_rule(rest, column + count.chars().count() + 1, "expected a bag colour followed by \"bag\" or \"bags\"")),
+            };
+
+            to_nodes.push((to_node.to_string(), weight));
+            column += item.chars().count() + 2;
+        }
+
+        return Ok(BagRule { bag: from_node.to_string(), contents: to_nodes });
+    }
+}
+
+fn malformed_rule(text: &str, column: usize, message: &str) -> InputError {
+    return InputError::new(ErrorKind::MalformedBagRule, message).with_text(text).at_column(column);
+}
+
+impl std::convert::TryFrom<&str> for DirectedGraph {
+    type Error = InputError;
+
+    // Parses a whole document of luggage rules.
+    fn try_from(text: &str) -> Result<DirectedGraph, InputError> {
+        return build_graph(&PuzzleInput::from_text(text).content_lines().to_vec());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BagRule;
+    use crate::DirectedGraph;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn parse_rules_and_documents() {
+        let rule: BagRule = "light red bags contain 1 bright white bag, 2 muted yellow bags.".parse().unwrap();
+        assert_eq!("light red", rule.bag);
+        assert_eq!(vec!((String::from("bright white"), 1), (String::from("muted yellow"), 2)), rule.contents);
+        assert!("faded blue bags contain no other bags.".parse::<BagRule>().unwrap().contents.is_empty());
+
+        let graph = DirectedGraph::try_from("a bags contain 2 b bags.\nb bags contain 3 c bags.\n").unwrap();
+        assert_eq!(8, graph.sum_of_path_weight_products("a").unwrap());
+    }
+}
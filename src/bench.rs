@@ -0,0 +1,296 @@
+use crate::ErrorKind;
+use crate::InputError;
+use crate::PuzzleInput;
+use crate::runner::catch_panics;
+
+pub struct BenchStats {
+    pub min: std::time::Duration,
+    pub median: std::time::Duration,
+    pub mean: std::time::Duration,
+    pub p95: std::time::Duration,
+}
+
+impl BenchStats {
+    pub fn from_samples(samples: &[std::time::Duration]) -> BenchStats {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        if sorted.is_empty() {
+            return BenchStats {
+                min: std::time::Duration::ZERO,
+                median: std::time::Duration::ZERO,
+                mean: std::time::Duration::ZERO,
+                p95: std::time::Duration::ZERO,
+            };
+        }
+
+        let total: std::time::Duration = sorted.iter().sum();
+
+        return BenchStats {
+            min: sorted[0],
+            median: BenchStats::percentile(&sorted, 50),
+            mean: total / sorted.len() as u32,
+            p95: BenchStats::percentile(&sorted, 95),
+        };
+    }
+
+    fn percentile(sorted: &[std::time::Duration], percent: usize) -> std::time::Duration {
+        // Nearest-rank percentile: the smallest sample with at least `percent`% of samples at or below it.
+        let rank = (percent * sorted.len()).div_ceil(100).max(1);
+
+        return sorted[rank - 1];
+    }
+}
+
+pub struct PartBench {
+    pub day: usize,
+    pub part: usize,
+    pub iterations: usize,
+    pub warmup: usize,
+    pub read: BenchStats,
+    pub parse: BenchStats,
+    pub solve: BenchStats,
+}
+
+// Read time covers loading the puzzle input with `PuzzleInput::from_file`, parse time covers
+// building the challenge's input from those lines, and solve time covers only the part itself.
+pub fn bench_part(
+    day: usize,
+    part: usize,
+    iterations: usize,
+    warmup: usize,
+    args: &[String],
+    params: &[(String, String)],
+) -> Result<PartBench, InputError> {
+    let mut challenge = (crate::challenge_by_day(&day.to_string())?)();
+    challenge.set_params(params)?;
+    let filepath = match args.first() {
+        Some(path) => path.clone(),
+        None => String::from(challenge.get_default_filepath()),
+    };
+    // Every iteration re-reads the input, which stdin can only supply once.
+    if filepath == "-" {
+        return Err(InputError::new(ErrorKind::InvalidParameter, "cannot benchmark input read from stdin"));
+    }
+
+    for _ in 0..warmup {
+        let raw_input = PuzzleInput::from_file(&filepath)?;
+        let input = catch_panics(|| challenge.parse_input(&raw_input)).map_err(|e| e.in_file(&filepath))?;
+        catch_panics(|| challenge.solve(part, &input))?;
+    }
+
+    let mut read_samples = Vec::with_capacity(iterations);
+    let mut parse_samples = Vec::with_capacity(iterations);
+    let mut solve_samples = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let read_start = std::time::Instant::now();
+        let raw_input = PuzzleInput::from_file(&filepath)?;
+        read_samples.push(read_start.elapsed());
+
+        let parse_start = std::time::Instant::now();
+        let input = catch_panics(|| challenge.parse_input(&raw_input)).map_err(|e| e.in_file(&filepath))?;
+        parse_samples.push(parse_start.elapsed());
+
+        let solve_start = std::time::Instant::now();
+        catch_panics(|| challenge.solve(part, &input))?;
+        solve_samples.push(solve_start.elapsed());
+    }
+
+    return Ok(
+        PartBench {
+            day: day,
+            part: part,
+            iterations: iterations,
+            warmup: warmup,
+            read: BenchStats::from_samples(&read_samples),
+            parse: BenchStats::from_samples(&parse_samples),
+            solve: BenchStats::from_samples(&solve_samples),
+        }
+    );
+}
+
+pub fn print_part_bench(out: &mut dyn std::io::Write, bench: &PartBench) -> std::io::Result<()> {
+    writeln!(out, "Day {} part {} ({} iterations, {} warm-up)", bench.day, bench.part, bench.iterations, bench.warmup)?;
+    writeln!(out, "  {:<6} {:>12} {:>12} {:>12} {:>12}", "", "min", "median", "mean", "p95")?;
+    for (label, stats) in [("read", &bench.read), ("parse", &bench.parse), ("solve", &bench.solve)] {
+        writeln!(
+            out,
+            "  {:<6} {:>12} {:>12} {:>12} {:>12}",
+            label,
+            format!("{:.3?}", stats.min),
+            format!("{:.3?}", stats.median),
+            format!("{:.3?}", stats.mean),
+            format!("{:.3?}", stats.p95),
+        )?;
+    }
+
+    return Ok(());
+}
+
+pub struct Baseline {
+    solve_medians: std::collections::BTreeMap<(usize, usize), std::time::Duration>,
+}
+
+impl Baseline {
+    pub fn new() -> Baseline {
+        return Baseline { solve_medians: std::collections::BTreeMap::new() };
+    }
+
+    // A missing file is an empty baseline so the first save can create it.
+    pub fn load(filepath: &str) -> Result<Baseline, InputError> {
+        let mut baseline = Baseline::new();
+        if !std::path::Path::new(filepath).exists() {
+            return Ok(baseline);
+        }
+
+        for (i, line) in crate::file_lines_to_string_vec(filepath)?.iter().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let entry = Baseline::parse_line(line).map_err(|e| e.at_line(i + 1).in_file(filepath))?;
+            baseline.solve_medians.insert((entry.0, entry.1), entry.2);
+        }
+
+        return Ok(baseline);
+    }
+
+    fn parse_line(line: &str) -> Result<(usize, usize, std::time::Duration), InputError> {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() != 3 {
+            return Err(InputError::new(ErrorKind::MalformedBaseline, "expected day, part and solve median").with_text(line));
+        }
+
+        let day = fields[0].parse::<usize>()?;
+        let part = fields[1].parse::<usize>()?;
+        let nanos = fields[2].parse::<u64>()?;
+
+        return Ok((day, part, std::time::Duration::from_nanos(nanos)));
+    }
+
+    pub fn save(&self, filepath: &str) -> Result<(), InputError> {
+        let mut contents = String::from("# day part solve_median_ns\n");
+        for ((day, part), median) in &self.solve_medians {
+            contents.push_str(&format!("{} {} {}\n", day, part, median.as_nanos()));
+        }
+
+        return std::fs::write(filepath, contents).map_err(|e| InputError::from(e).in_file(filepath));
+    }
+
+    pub fn record(&mut self, bench: &PartBench) {
+        self.solve_medians.insert((bench.day, bench.part), bench.solve.median);
+    }
+
+    pub fn get(&self, day: usize, part: usize) -> Option<std::time::Duration> {
+        return self.solve_medians.get(&(day, part)).copied();
+    }
+}
+
+impl Default for Baseline {
+    fn default() -> Baseline {
+        return Baseline::new();
+    }
+}
+
+pub struct Comparison {
+    pub day: usize,
+    pub part: usize,
+    pub baseline: Option<std::time::Duration>,
+    pub current: std::time::Duration,
+    pub percent_change: Option<f64>,
+    pub regressed: bool,
+}
+
+pub fn compare_to_baseline(bench: &PartBench, baseline: &Baseline, threshold_percent: f64) -> Comparison {
+    let current = bench.solve.median;
+    let baseline_median = baseline.get(bench.day, bench.part);
+
+    let percent_change = baseline_median
+        .filter(|b| !b.is_zero())
+        .map(|b| (current.as_secs_f64() / b.as_secs_f64() - 1.0) * 100.0);
+
+    return Comparison {
+        day: bench.day,
+        part: bench.part,
+        baseline: baseline_median,
+        current: current,
+        percent_change: percent_change,
+        regressed: percent_change.is_some_and(|p| p > threshold_percent),
+    };
+}
+
+pub fn print_comparison(out: &mut dyn std::io::Write, comparison: &Comparison) -> std::io::Result<()> {
+    return match (comparison.baseline, comparison.percent_change) {
+        (Some(baseline), Some(change)) => writeln!(
+            out,
+            "  solve median {:.3?} vs baseline {:.3?} ({:+.1}%){}",
+            comparison.current,
+            baseline,
+            change,
+            if comparison.regressed { "  REGRESSION" } else { "" },
+        ),
+        _ => writeln!(out, "  solve median {:.3?}, no baseline entry", comparison.current),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BenchStats;
+
+    #[test]
+    fn stats_from_samples() {
+        let samples: Vec<std::time::Duration> = (1..=20).rev()
+            .map(std::time::Duration::from_millis)
+            .collect();
+
+        let stats = BenchStats::from_samples(&samples);
+
+        assert_eq!(std::time::Duration::from_millis(1), stats.min);
+        assert_eq!(std::time::Duration::from_millis(10), stats.median);
+        assert_eq!(std::time::Duration::from_micros(10500), stats.mean);
+        assert_eq!(std::time::Duration::from_millis(19), stats.p95);
+    }
+
+    #[test]
+    fn baseline_round_trip_and_regression() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let filepath = file.path().to_str().unwrap();
+
+        let mut bench = super::bench_part(1, 1, 1, 0, &[], &[]).unwrap();
+        bench.solve.median = std::time::Duration::from_millis(10);
+
+        let mut baseline = super::Baseline::new();
+        baseline.record(&bench);
+        baseline.save(filepath).unwrap();
+
+        let loaded = super::Baseline::load(filepath).unwrap();
+        assert_eq!(Some(std::time::Duration::from_millis(10)), loaded.get(1, 1));
+        assert_eq!(None, loaded.get(1, 2));
+
+        bench.solve.median = std::time::Duration::from_millis(12);
+        assert!(super::compare_to_baseline(&bench, &loaded, 10.0).regressed);
+        assert!(!super::compare_to_baseline(&bench, &loaded, 25.0).regressed);
+    }
+
+    #[test]
+    fn bench_part_counts_iterations() {
+        let bench = super::bench_part(1, 1, 3, 1, &[], &[]).unwrap();
+
+        assert_eq!(3, bench.iterations);
+        assert!(bench.read.min <= bench.read.p95);
+        assert!(bench.parse.min <= bench.parse.p95);
+        assert!(bench.solve.min <= bench.solve.p95);
+    }
+
+    #[test]
+    fn parse_errors_name_the_input_file() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut file, b"1721\nabc\n").unwrap();
+        let args = vec!(String::from(file.path().to_str().unwrap()));
+
+        let error = super::bench_part(1, 1, 1, 0, &args, &[]).err().unwrap();
+        assert_eq!(Some(args[0].as_str()), error.file());
+        assert_eq!(Some(2), error.line());
+    }
+}
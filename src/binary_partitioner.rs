@@ -1,19 +1,24 @@
+use crate::ErrorKind;
 use crate::InputError;
 
 pub fn partition(input: &String) -> Result<std::collections::HashMap<String, usize>, InputError> {
     let mut row_range = [0, max_row_num()];
     let mut column_range = [0, max_column_num()];
 
-    for instruction in input.chars() {
-        let new_row_count = (row_range[1] - row_range[0] + 1) / 2;
-        let new_column_count = (column_range[1] - column_range[0] + 1) / 2;
+    for (i, instruction) in input.chars().enumerate() {
+        let new_row_count = (row_range[1] - row_range[0]).div_ceil(2);
+        let new_column_count = (column_range[1] - column_range[0]).div_ceil(2);
 
         match instruction {
             'F' => row_range = [row_range[0], row_range[1] - new_row_count],
             'B' => row_range = [row_range[0] + new_row_count, row_range[1]],
             'L' => column_range = [column_range[0], column_range[1] - new_column_count],
             'R' => column_range = [column_range[0] + new_column_count, column_range[1]],
-            _ => return Err(InputError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, "Bad input string"))),
+            _ => return Err(
+                InputError::new(ErrorKind::MalformedBoardingPass, "expected one of F, B, L or R")
+                    .with_text(&instruction.to_string())
+                    .at_column(i + 1)
+            ),
         };
     }
 
@@ -26,6 +31,29 @@ pub fn partition(input: &String) -> Result<std::collections::HashMap<String, usi
     );
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BoardingPass {
+    pub row: usize,
+    pub column: usize,
+}
+
+impl BoardingPass {
+    pub fn seat_id(&self) -> usize {
+        return self.row * 8 + self.column;
+    }
+}
+
+impl std::str::FromStr for BoardingPass {
+    type Err = InputError;
+
+    fn from_str(input: &str) -> Result<BoardingPass, InputError> {
+        let seat = partition(&String::from(input))?;
+
+        return Ok(BoardingPass { row: seat["row"], column: seat["column"] });
+    }
+}
+
 fn max_row_num() -> usize {
     return 127;
 }
@@ -52,6 +80,15 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_boarding_pass() {
+        let boarding_pass: super::BoardingPass = "FBFBBFFRLR".parse().unwrap();
+
+        assert_eq!(super::BoardingPass { row: 44, column: 5 }, boarding_pass);
+        assert_eq!(357, boarding_pass.seat_id());
+        assert_eq!(Some(3), "FBXBBFFRLR".parse::<super::BoardingPass>().err().and_then(|e| e.column()));
+    }
+
     partitioner_tests! {
         binary_partition_1: ("FBFBBFFRLR", [44, 5]),
         binary_partition_2: ("BFFFBBFRRR", [70, 7]),
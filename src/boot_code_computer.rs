@@ -1,29 +1,79 @@
+use crate::input_error::ErrorKind;
+use crate::input_error::InputError;
+use crate::input::PuzzleInput;
+
+// A single line of boot code, such as "acc +3".
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "InstructionFields", try_from = "InstructionFields"))]
+pub struct Instruction {
+    pub operation: String,
+    pub argument: i64,
+}
+
+const OPERATIONS: [&str; 3] = ["nop", "acc", "jmp"];
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct InstructionFields {
+    operation: String,
+    argument: i64,
+}
+
+#[cfg(feature = "serde")]
+impl From<Instruction> for InstructionFields {
+    fn from(instruction: Instruction) -> InstructionFields {
+        return InstructionFields { operation: instruction.operation, argument: instruction.argument };
+    }
+}
+
+#[cfg(feature = "serde")]
+impl std::convert::TryFrom<InstructionFields> for Instruction {
+    type Error = InputError;
+
+    fn try_from(fields: InstructionFields) -> Result<Instruction, InputError> {
+        if !OPERATIONS.contains(&fields.operation.as_str()) {
+            return Err(malformed_instruction(&fields.operation, 1, "expected nop, acc or jmp"));
+        }
+
+        return Ok(Instruction { operation: fields.operation, argument: fields.argument });
+    }
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BootCodeComputer {
     accumulator: i64,
-    code: Vec<(String, i64)>,
+    #[cfg_attr(feature = "serde", serde(rename = "program"))]
+    code: Vec<Instruction>,
+    #[cfg_attr(feature = "serde", serde(rename = "instruction_pointer"))]
     instruction_index: usize,
 }
 
 impl BootCodeComputer {
-    pub fn new(code: &Vec<String>) -> BootCodeComputer {
+    pub fn new(code: &Vec<String>) -> Result<BootCodeComputer, InputError> {
         let mut parsed_code = Vec::new();
-        for i in code {
-            parsed_code.push(BootCodeComputer::parse_instruction(i));
+        for (line_index, i) in code.iter().enumerate() {
+            parsed_code.push(
+                i.parse::<Instruction>().map_err(|e| e.with_line_text(i).at_line(line_index + 1))?
+            );
         }
-        return BootCodeComputer {
-            accumulator: 0,
-            code: parsed_code,
-            instruction_index: 0,
-        };
+        return Ok(
+            BootCodeComputer {
+                accumulator: 0,
+                code: parsed_code,
+                instruction_index: 0,
+            }
+        );
     }
 
     pub fn get_accumulator_value(&self) -> i64 {
         return self.accumulator;
     }
 
-    pub fn run_until_complete_with_instruction_swaps(&mut self) {
+    pub fn run_until_complete_with_instruction_swaps(&mut self) -> Result<(), InputError> {
         let swappable_indices: Vec<usize> = self.code.iter().enumerate()
-            .filter(|e| e.1.0 == "nop" || e.1.0 == "jmp")
+            .filter(|e| e.1.operation == "nop" || e.1.operation == "jmp")
             .map(|e| e.0)
             .collect();
 
@@ -32,56 +82,94 @@ impl BootCodeComputer {
             self.instruction_index = 0;
             self.swap_instruction(*swap_index);
 
-            self.run_until_first_loop();
+            self.run_until_first_loop()?;
             if self.instruction_index == self.code.len() {
-                return;
+                return Ok(());
             }
 
             self.swap_instruction(*swap_index);
         }
+
+        return Err(InputError::new(ErrorKind::NoSolution, "no single nop/jmp swap lets the program finish"));
     }
 
     fn swap_instruction(&mut self, index: usize) {
-        match &self.code[index].0 as &str {
-            "nop" => self.code[index].0 = "jmp".to_string(),
-            "jmp" => self.code[index].0 = "nop".to_string(),
+        match &self.code[index].operation as &str {
+            "nop" => self.code[index].operation = "jmp".to_string(),
+            "jmp" => self.code[index].operation = "nop".to_string(),
             _ => {},
         }
     }
 
-    pub fn run_until_first_loop(&mut self) {
+    pub fn run_until_first_loop(&mut self) -> Result<(), InputError> {
         let mut executed_indices = std::collections::HashSet::<usize>::new();
         while self.instruction_index < self.code.len() && !executed_indices.contains(&self.instruction_index) {
             executed_indices.insert(self.instruction_index);
-            self.execute_current_instruction();
+            self.execute_current_instruction()?;
         }
+
+        return Ok(());
     }
 
-    fn execute_current_instruction(&mut self) {
-        match &self.code[self.instruction_index].0 as &str {
-            "nop" => self.instruction_index += 1,
+    // A jump outside the program stops it; only a jump to exactly one past the end counts as finishing.
+    fn execute_current_instruction(&mut self) -> Result<(), InputError> {
+        let argument = self.code[self.instruction_index].argument;
+        match &self.code[self.instruction_index].operation as &str {
             "acc" => {
-                self.accumulator += (self.code[self.instruction_index]).1;
+                self.accumulator = match self.accumulator.checked_add(argument) {
+                    Some(a) => a,
+                    None => return Err(InputError::new(ErrorKind::NoSolution, "accumulator overflowed")),
+                };
                 self.instruction_index += 1;
             },
             "jmp" => {
-                let new_index = self.instruction_index as i64 + (self.code[self.instruction_index]).1;
-                self.instruction_index = new_index as usize;
+                self.instruction_index = (self.instruction_index as i64).checked_add(argument)
+                    .and_then(|i| std::convert::TryFrom::try_from(i).ok())
+                    .unwrap_or(usize::MAX);
             },
-            _ => panic!("Bad boot code instruction"),
+            _ => self.instruction_index += 1,
+        }
+
+        return Ok(());
+    }
+}
+
+impl std::str::FromStr for Instruction {
+    type Err = InputError;
+
+    // Instructions look like "acc +3": an operation, a space and a signed argument.
+    fn from_str(instruction: &str) -> Result<Instruction, InputError> {
+        let (operation, argument) = match instruction.split_once(' ') {
+            Some(parts) => parts,
+            None => return Err(malformed_instruction(instruction, 1, "expected an operation and an argument")),
+        };
+        if !OPERATIONS.contains(&operation) {
+            return Err(malformed_instruction(operation, 1, "expected nop, acc or jmp"));
         }
+
+        let value = argument.parse::<i64>()
+            .map_err(|e| malformed_instruction(argument, operation.chars().count() + 2, "expected a signed number").with_source(e))?;
+
+        return Ok(Instruction { operation: operation.to_string(), argument: value });
     }
+}
 
-    fn parse_instruction(instruction: &String) -> (String, i64) {
-        let parts: Vec<&str> = instruction.splitn(2, " ").collect();
-        let value = parts[1].parse::<i64>().unwrap();
+impl std::convert::TryFrom<&str> for BootCodeComputer {
+    type Error = InputError;
 
-        return ((*parts[0]).to_string(), value);
+    fn try_from(text: &str) -> Result<BootCodeComputer, InputError> {
+        return BootCodeComputer::new(&PuzzleInput::from_text(text).content_lines().to_vec());
     }
 }
 
+fn malformed_instruction(text: &str, column: usize, message: &str) -> InputError {
+    return InputError::new(ErrorKind::MalformedInstruction, message).with_text(text).at_column(column);
+}
+
 #[cfg(test)]
 mod tests {
+    use std::convert::TryFrom;
+
     #[test]
     fn accumulator_value_at_first_loop() {
         let input = vec!(
@@ -96,8 +184,8 @@ mod tests {
             "acc +6".to_string(),
         );
 
-        let mut computer = super::BootCodeComputer::new(&input);
-        computer.run_until_first_loop();
+        let mut computer = super::BootCodeComputer::new(&input).unwrap();
+        computer.run_until_first_loop().unwrap();
         let actual = computer.get_accumulator_value();
 
         assert_eq!(5, actual);
@@ -117,10 +205,60 @@ mod tests {
             "acc +6".to_string(),
         );
 
-        let mut computer = super::BootCodeComputer::new(&input);
-        computer.run_until_complete_with_instruction_swaps();
+        let mut computer = super::BootCodeComputer::new(&input).unwrap();
+        computer.run_until_complete_with_instruction_swaps().unwrap();
         let actual = computer.get_accumulator_value();
 
         assert_eq!(8, actual);
     }
+
+    #[test]
+    fn unknown_operation_is_located() {
+        let input = vec!("nop +0".to_string(), "hop +1".to_string());
+
+        let error = match super::BootCodeComputer::new(&input) {
+            Err(e) => e,
+            Ok(_) => panic!("expected a malformed instruction"),
+        };
+
+        assert_eq!(Some(2), error.line());
+        assert_eq!(Some(1), error.column());
+        assert_eq!(Some("hop"), error.text());
+        assert_eq!(Some("hop +1"), error.line_text());
+    }
+
+    #[test]
+    fn parse_instructions_and_programs() {
+        let instruction: super::Instruction = "jmp -4".parse().unwrap();
+        assert_eq!(super::Instruction { operation: String::from("jmp"), argument: -4 }, instruction);
+        assert_eq!(Some(5), "acc x".parse::<super::Instruction>().err().and_then(|e| e.column()));
+
+        let mut computer = super::BootCodeComputer::try_from("acc +2\njmp -1\n").unwrap();
+        computer.run_until_first_loop().unwrap();
+        assert_eq!(2, computer.get_accumulator_value());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip_keeps_execution_state() {
+        let mut computer = super::BootCodeComputer::try_from("acc +2\nacc +3\njmp -2\n").unwrap();
+        computer.execute_current_instruction().unwrap();
+
+        let json = serde_json::to_string(&computer).unwrap();
+        assert_eq!(
+            r#"{"accumulator":2,"program":[{"operation":"acc","argument":2},{"operation":"acc","argument":3},{"operation":"jmp","argument":-2}],"instruction_pointer":1}"#,
+            json
+        );
+
+        let mut restored: super::BootCodeComputer = serde_json::from_str(&json).unwrap();
+        restored.run_until_first_loop().unwrap();
+        assert_eq!(7, restored.get_accumulator_value());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_rejects_unknown_operations() {
+        let json = r#"{"accumulator":0,"program":[{"operation":"mul","argument":2}],"instruction_pointer":0}"#;
+        assert!(serde_json::from_str::<super::BootCodeComputer>(json).is_err());
+    }
 }
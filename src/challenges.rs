@@ -1,4 +1,6 @@
-use crate::ChallengeFn;
+use crate::ErrorKind;
+use crate::InputError;
+use crate::PuzzleInput;
 use crate::ResultHashMap;
 
 pub mod day1;
@@ -12,25 +14,198 @@ pub mod day8;
 pub mod day9;
 pub mod day10;
 
-pub struct Challenge {
-    functions: [ChallengeFn; 2],
-    default_filepath: String,
+#[derive(Clone, Debug)]
+pub struct ChallengeMetadata {
+    pub title: &'static str,
+    pub puzzle_name: &'static str,
+    pub input_description: &'static str,
+    pub parts: &'static [usize],
+    pub default_filepath: &'static str,
 }
 
-impl Challenge {
-    pub fn new(fn_part1: ChallengeFn, fn_part2: ChallengeFn, default_filepath: String) -> Challenge {
-        return Challenge {
-            functions: [fn_part1, fn_part2],
-            default_filepath: default_filepath,
+pub type Solver<C> = fn(&C, &<C as Challenge>::Input) -> ResultHashMap;
+
+pub trait Challenge {
+    type Input: 'static;
+
+    fn get_metadata(&self) -> ChallengeMetadata;
+
+    fn parse(&self, input: &PuzzleInput) -> Result<Self::Input, InputError>;
+
+    fn part1(&self, input: &Self::Input) -> ResultHashMap;
+
+    fn part2(&self, input: &Self::Input) -> ResultHashMap;
+
+    // Further named solvers for a part, such as a brute-force reference next to an optimised
+    // `part1`/`part2`, which is always available as "default".
+    fn alternatives(&self, _part: usize) -> Vec<(&'static str, Solver<Self>)> where Self: Sized {
+        return Vec::new();
+    }
+
+    fn get_params(&self) -> Vec<(String, String)> {
+        return Vec::new();
+    }
+
+    fn set_param(&mut self, name: &str, _value: &str) -> Result<(), InputError> {
+        return Err(unknown_param(name));
+    }
+}
+
+pub struct ParsedInput {
+    value: Box<dyn std::any::Any>,
+}
+
+// Object-safe view of a `Challenge`, so days with different input types can share a registry.
+pub trait DynChallenge {
+    fn get_metadata(&self) -> ChallengeMetadata;
+
+    fn get_default_filepath(&self) -> &'static str {
+        return self.get_metadata().default_filepath;
+    }
+
+    fn parse_input(&self, input: &PuzzleInput) -> Result<ParsedInput, InputError>;
+
+    fn solve(&self, part: usize, input: &ParsedInput) -> ResultHashMap;
+
+    // "default" first, then any alternatives; empty for parts the challenge does not have.
+    fn implementation_names(&self, part: usize) -> Vec<&'static str>;
+
+    fn solve_with(&self, part: usize, implementation: &str, input: &ParsedInput) -> ResultHashMap;
+
+    fn get_params(&self) -> Vec<(String, String)>;
+
+    fn set_param(&mut self, name: &str, value: &str) -> Result<(), InputError>;
+
+    fn set_params(&mut self, params: &[(String, String)]) -> Result<(), InputError> {
+        for (name, value) in params {
+            self.set_param(name, value)?;
         }
+
+        return Ok(());
+    }
+
+    fn run_fn(&self, part: usize, args: &[String]) -> ResultHashMap {
+        let filepath = match args.first() {
+            Some(path) => path.as_str(),
+            None => self.get_default_filepath(),
+        };
+
+        let input = PuzzleInput::from_file(filepath)?;
+
+        return self.run_input(part, &input).map_err(|e| e.in_file(filepath));
+    }
+
+    fn run_str(&self, part: usize, input: &str) -> ResultHashMap {
+        return self.run_input(part, &PuzzleInput::from_text(input));
+    }
+
+    fn run_reader(&self, part: usize, reader: &mut dyn std::io::BufRead) -> ResultHashMap {
+        return self.run_input(part, &PuzzleInput::from_reader(reader)?);
+    }
+
+    fn run_input(&self, part: usize, input: &PuzzleInput) -> ResultHashMap {
+        let parsed = self.parse_input(input)?;
+
+        return self.solve(part, &parsed);
     }
+}
 
-    pub fn run_fn(&self, part: usize, args: &[String]) -> ResultHashMap {
-        if args.len() > 0 {
-            return (self.functions[part - 1])(args);
-        } else {
-            return (self.functions[part - 1])(&[self.default_filepath.clone()]);
+impl<T: Challenge> DynChallenge for T {
+    fn get_metadata(&self) -> ChallengeMetadata {
+        return Challenge::get_metadata(self);
+    }
+
+    fn parse_input(&self, input: &PuzzleInput) -> Result<ParsedInput, InputError> {
+        return Ok(ParsedInput { value: Box::new(self.parse(input)?) });
+    }
+
+    fn solve(&self, part: usize, input: &ParsedInput) -> ResultHashMap {
+        let input = downcast_input::<T>(input)?;
+
+        if !Challenge::get_metadata(self).parts.contains(&part) {
+            return Err(unknown_part(part));
+        }
+
+        return match part {
+            1 => self.part1(input),
+            2 => self.part2(input),
+            _ => Err(unknown_part(part)),
+        };
+    }
+
+    fn implementation_names(&self, part: usize) -> Vec<&'static str> {
+        if !Challenge::get_metadata(self).parts.contains(&part) {
+            return Vec::new();
+        }
+
+        let mut names = vec!("default");
+        names.extend(self.alternatives(part).iter().map(|a| a.0));
+
+        return names;
+    }
+
+    fn solve_with(&self, part: usize, implementation: &str, input: &ParsedInput) -> ResultHashMap {
+        if implementation == "default" {
+            return self.solve(part, input);
         }
+
+        return match self.alternatives(part).iter().find(|a| a.0 == implementation) {
+            Some((_, solver)) => solver(self, downcast_input::<T>(input)?),
+            None => Err(
+                InputError::new(ErrorKind::UnknownImplementation, format!("part {} has no implementation named {}", part, implementation))
+                    .with_text(implementation)
+            ),
+        };
+    }
+
+    fn get_params(&self) -> Vec<(String, String)> {
+        return Challenge::get_params(self);
+    }
+
+    fn set_param(&mut self, name: &str, value: &str) -> Result<(), InputError> {
+        return Challenge::set_param(self, name, value);
+    }
+}
+
+pub fn unknown_param(name: &str) -> InputError {
+    return InputError::new(ErrorKind::UnknownParameter, format!("unknown parameter: {}", name)).with_text(name);
+}
+
+pub fn parse_param_value<T>(name: &str, value: &str) -> Result<T, InputError>
+where
+    T: std::str::FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    return value.parse::<T>().map_err(|e|
+        InputError::new(ErrorKind::InvalidParameter, format!("bad value for parameter {}", name))
+            .with_source(e)
+            .with_text(value)
+    );
+}
+
+fn downcast_input<T: Challenge>(input: &ParsedInput) -> Result<&T::Input, InputError> {
+    return match input.value.downcast_ref::<T::Input>() {
+        Some(i) => Ok(i),
+        None => Err(InputError::new(ErrorKind::InputMismatch, "parsed input belongs to another challenge")),
+    };
+}
+
+fn unknown_part(part: usize) -> InputError {
+    return InputError::new(ErrorKind::UnknownPart, format!("no part {}", part));
+}
+
+// Expected literals may exceed any single integer type, so answers are compared by printed form,
+// with the quotes of string literals dropped. Every result key must be listed.
+#[doc(hidden)]
+pub fn assert_answers(part: usize, expected: &[(&str, &str)], actual: &std::collections::HashMap<String, crate::Answer>) {
+    let mut expected_keys: Vec<&str> = expected.iter().map(|e| e.0).collect();
+    let mut actual_keys: Vec<&str> = actual.keys().map(|k| k.as_str()).collect();
+    expected_keys.sort();
+    actual_keys.sort();
+    assert_eq!(expected_keys, actual_keys, "part {} answers have different keys", part);
+
+    for (key, value) in expected {
+        assert_eq!(value.trim_matches('"'), actual[*key].to_string(), "part {} answer {:?}", part, key);
     }
 }
 
@@ -40,17 +215,58 @@ macro_rules! challenge_test {
         #[test]
         fn $name() {
             let challenge = challenge();
-            let actual = challenge.run_fn($part, &[challenge.default_filepath.clone()]).unwrap();
+            let actual = challenge.run_fn($part, &[]).unwrap();
 
-            assert_eq!($expected, *actual.values().next().unwrap());
+            assert_eq!(1, actual.len(), "part {} has more than one answer; list them by key", $part);
+            let key = actual.keys().next().unwrap().clone();
+            $crate::challenges::assert_answers($part, &[(&key, stringify!($expected))], &actual);
         }
-    }
+    };
+    ($name:ident, $part:literal, { $($key:literal: $expected:literal),* $(,)? }) => {
+        #[test]
+        fn $name() {
+            let challenge = challenge();
+            let actual = challenge.run_fn($part, &[]).unwrap();
+
+            $crate::challenges::assert_answers($part, &[$(($key, stringify!($expected))),*], &actual);
+        }
+    };
 }
 
 #[macro_export]
 macro_rules! challenge_tests {
-    ($part1_expected:literal, $part2_expected:literal) => {
-        crate::challenge_test!(test_part1, 1, $part1_expected);
-        crate::challenge_test!(test_part2, 2, $part2_expected);
+    ($part1_expected:tt, $part2_expected:tt) => {
+        $crate::challenge_test!(test_part1, 1, $part1_expected);
+        $crate::challenge_test!(test_part2, 2, $part2_expected);
     }
 }
+
+// Each example input file becomes its own test, checking every listed part and result key, e.g.
+//
+//     challenge_examples! {
+//         example: "resources/examples/day9/example.txt" with { "preamble": "5" } => {
+//             1: { "target number": 127 },
+//         },
+//     }
+#[macro_export]
+macro_rules! challenge_examples {
+    ($(
+        $name:ident: $filepath:literal $(with { $($param:literal: $value:literal),* $(,)? })? => {
+            $($part:literal: { $($key:literal: $expected:literal),* $(,)? }),* $(,)?
+        }
+    ),* $(,)?) => {
+        $(
+            #[test]
+            fn $name() {
+                let mut challenge = challenge();
+                challenge.set_params(&[$($((String::from($param), String::from($value))),*)?]).unwrap();
+                let args = [String::from($filepath)];
+
+                $(
+                    let actual = challenge.run_fn($part, &args).unwrap();
+                    $crate::challenges::assert_answers($part, &[$(($key, stringify!($expected))),*], &actual);
+                )*
+            }
+        )*
+    };
+}
@@ -1,38 +1,93 @@
 use crate::challenges::Challenge;
+use crate::challenges::ChallengeMetadata;
+use crate::challenges::DynChallenge;
+use crate::challenges::parse_param_value;
+use crate::challenges::unknown_param;
+use crate::Amount;
+use crate::Answer;
+use crate::ErrorKind;
+use crate::InputError;
+use crate::PuzzleInput;
 use crate::ResultHashMap;
-use crate::file_lines_to_string_vec;
+use crate::EntrySelection;
 use crate::ExpenseReport;
 
-pub fn challenge() -> Challenge {
-    return Challenge::new(
-        expense_report_product_from_2_sum,
-        expense_report_product_from_3_sum,
-        String::from("resources/expense_report.txt"),
-    );
+pub struct Day1 {
+    target_sum: Amount,
+    with_replacement: bool,
 }
 
-pub fn expense_report_product_from_2_sum(args: &[String]) -> ResultHashMap {
-    let raw_lines = file_lines_to_string_vec(&args[0])?;
+impl Day1 {
+    pub fn new() -> Day1 {
+        return Day1 { target_sum: Amount::whole(2020), with_replacement: false };
+    }
 
-    let mut expense_report = ExpenseReport::new(&raw_lines)?;
+    fn selection(&self) -> EntrySelection {
+        return if self.with_replacement { EntrySelection::WithReplacement } else { EntrySelection::Distinct };
+    }
+}
 
-    let target_sum = 2020;
-    return Ok(
-        [(String::from("expense report values product"), expense_report.product_from_target_two_sum(target_sum))]
-        .iter()
-        .cloned()
-        .collect()
-    );
+impl Default for Day1 {
+    fn default() -> Day1 {
+        return Day1::new();
+    }
 }
 
-fn expense_report_product_from_3_sum(args: &[String]) -> ResultHashMap {
-    let raw_lines = file_lines_to_string_vec(&args[0])?;
+pub fn challenge() -> Box<dyn DynChallenge> {
+    return Box::new(Day1::new());
+}
 
-    let mut expense_report = ExpenseReport::new(&raw_lines)?;
+impl Challenge for Day1 {
+    type Input = ExpenseReport;
+
+    fn get_metadata(&self) -> ChallengeMetadata {
+        return ChallengeMetadata {
+            title: "Report Repair",
+            puzzle_name: "expense report",
+            input_description: "One expense amount per line",
+            parts: &[1, 2],
+            default_filepath: "resources/expense_report.txt",
+        };
+    }
+
+    fn parse(&self, input: &PuzzleInput) -> Result<ExpenseReport, InputError> {
+        return ExpenseReport::new(&input.content_lines().to_vec());
+    }
+
+    fn part1(&self, expense_report: &ExpenseReport) -> ResultHashMap {
+        return expense_report_product_from_k_sum(expense_report, 2, self.target_sum, self.selection());
+    }
+
+    fn part2(&self, expense_report: &ExpenseReport) -> ResultHashMap {
+        return expense_report_product_from_k_sum(expense_report, 3, self.target_sum, self.selection());
+    }
+
+    fn get_params(&self) -> Vec<(String, String)> {
+        return vec!(
+            (String::from("target"), self.target_sum.to_string()),
+            (String::from("replacement"), self.with_replacement.to_string()),
+        );
+    }
+
+    fn set_param(&mut self, name: &str, value: &str) -> Result<(), InputError> {
+        match name {
+            "target" => self.target_sum = parse_param_value(name, value)?,
+            "replacement" => self.with_replacement = parse_param_value(name, value)?,
+            _ => return Err(unknown_param(name)),
+        }
+
+        return Ok(());
+    }
+}
+
+pub fn expense_report_product_from_k_sum(expense_report: &ExpenseReport, k: usize, target_sum: Amount, selection: EntrySelection) -> ResultHashMap {
+    let product = match expense_report.target_k_sum(k, target_sum, selection)? {
+        Some(found) => found.product(),
+        None => return Err(InputError::new(ErrorKind::NoSolution, format!("no {} expense entries sum to {}", k, target_sum))),
+    };
 
-    let target_sum = 2020;
     return Ok(
-        [(String::from("expense report values product"), expense_report.product_from_target_three_sum(target_sum))]
+        [(String::from("expense report values product"), Answer::from(product))]
         .iter()
         .cloned()
         .collect()
@@ -44,4 +99,75 @@ mod tests {
     use super::challenge;
 
     crate::challenge_tests!(121396, 73616634);
+
+    crate::challenge_examples! {
+        example: "resources/examples/day1/example.txt" => {
+            1: { "expense report values product": 514579 },
+            2: { "expense report values product": 241861950 },
+        },
+    }
+
+    #[test]
+    fn target_param_overrides_default() {
+        let mut challenge = challenge();
+        let input = crate::PuzzleInput::from_text("1721\n979\n366\n299\n675\n1456\n");
+        let solve = |challenge: &dyn crate::challenges::DynChallenge| challenge.solve(1, &challenge.parse_input(&input).unwrap()).unwrap();
+        assert_eq!(crate::Answer::from(514579_usize), solve(challenge.as_ref())["expense report values product"]);
+
+        challenge.set_params(&[(String::from("target"), String::from("1345"))]).unwrap();
+
+        assert_eq!(
+            vec!((String::from("target"), String::from("1345")), (String::from("replacement"), String::from("false"))),
+            challenge.get_params()
+        );
+        assert_eq!(crate::Answer::from(358314_usize), solve(challenge.as_ref())["expense report values product"]);
+        assert!(challenge.set_params(&[(String::from("preamble"), String::from("5"))]).is_err());
+    }
+
+    #[test]
+    fn matches_report_their_lines_sum_and_product() {
+        let report = crate::ExpenseReport::new(&vec!(
+            String::from("1721"),
+            String::from("979"),
+            String::from("366"),
+            String::from("299"),
+            String::from("675"),
+            String::from("1456"),
+        )).unwrap();
+        let expense_match = report.target_three_sum(crate::Amount::whole(2020)).unwrap().unwrap();
+
+        assert_eq!(vec!(2, 3, 5), expense_match.lines());
+        assert_eq!(crate::Amount::whole(2020), expense_match.sum());
+        assert_eq!("241861950", expense_match.product().to_string());
+    }
+
+    #[test]
+    fn replacement_param_allows_reusing_a_line() {
+        let mut challenge = challenge();
+        let input = crate::PuzzleInput::from_text("1010\n5\n");
+        let solve = |challenge: &dyn crate::challenges::DynChallenge| challenge.solve(1, &challenge.parse_input(&input).unwrap());
+
+        assert_eq!(crate::ErrorKind::NoSolution, solve(challenge.as_ref()).unwrap_err().kind());
+        challenge.set_params(&[(String::from("replacement"), String::from("true"))]).unwrap();
+        assert_eq!(crate::Answer::from(1020100_usize), solve(challenge.as_ref()).unwrap()["expense report values product"]);
+    }
+
+    #[test]
+    fn trailing_blank_lines_are_ignored() {
+        let challenge = challenge();
+        let parsed = challenge.parse_input(&crate::PuzzleInput::from_text("1721\n979\n366\n299\n675\n1456\n\n  \n")).unwrap();
+
+        assert_eq!(crate::Answer::from(514579_usize), challenge.solve(1, &parsed).unwrap()["expense report values product"]);
+    }
+
+    #[test]
+    fn decimal_targets_and_refunds() {
+        let mut challenge = challenge();
+        challenge.set_params(&[(String::from("target"), String::from("12.5"))]).unwrap();
+        let parsed = challenge.parse_input(&crate::PuzzleInput::from_text("12.34\n7\n-0.5\n0.16\n6\n")).unwrap();
+
+        assert_eq!(crate::Answer::from("1.9744"), challenge.solve(1, &parsed).unwrap()["expense report values product"]);
+        assert_eq!(crate::Answer::from("-21.000000"), challenge.solve(2, &parsed).unwrap()["expense report values product"]);
+        assert!(challenge.set_params(&[(String::from("target"), String::from("12,5"))]).is_err());
+    }
 }
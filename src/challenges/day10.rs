@@ -1,28 +1,65 @@
 use crate::challenges::Challenge;
+use crate::challenges::ChallengeMetadata;
+use crate::challenges::DynChallenge;
+use crate::challenges::Solver;
+use crate::Answer;
+use crate::ErrorKind;
+use crate::InputError;
+use crate::PuzzleInput;
 use crate::ResultHashMap;
-use crate::file_lines_to_string_vec;
-use crate::n_ary_tree;
-
-pub fn challenge() -> Challenge {
-    return Challenge::new(
-        product_of_sorted_value_differences,
-        count_valid_joltage_paths,
-        String::from("resources/joltage_ratings.txt"),
-    );
+
+pub struct Day10;
+
+pub fn challenge() -> Box<dyn DynChallenge> {
+    return Box::new(Day10);
 }
 
-fn product_of_sorted_value_differences(args: &[String]) -> ResultHashMap {
-    let raw_lines = file_lines_to_string_vec(&args[0])?;
+impl Challenge for Day10 {
+    type Input = Vec<usize>;
+
+    fn get_metadata(&self) -> ChallengeMetadata {
+        return ChallengeMetadata {
+            title: "Adapter Array",
+            puzzle_name: "joltage ratings",
+            input_description: "One adapter joltage rating per line",
+            parts: &[1, 2],
+            default_filepath: "resources/joltage_ratings.txt",
+        };
+    }
 
-    let mut values: Vec<usize> = raw_lines.iter()
-        .map(|l| l.parse().unwrap())
-        .collect();
-    values.sort();
-    values.insert(0, 0);
-    values.push(values.last().unwrap() + 3);
+    fn parse(&self, input: &PuzzleInput) -> Result<Vec<usize>, InputError> {
+        let mut values: Vec<usize> = input.integers()?;
+        values.sort();
+        values.insert(0, 0);
+
+        let device_joltage = match values[values.len() - 1].checked_add(3) {
+            Some(j) => j,
+            None => return Err(InputError::new(ErrorKind::NoSolution, "adapter joltage is too large for the device to follow")),
+        };
+        values.push(device_joltage);
+
+        return Ok(values);
+    }
 
-    let mut one_diffs = 0;
-    let mut three_diffs = 0;
+    fn part1(&self, values: &Vec<usize>) -> ResultHashMap {
+        return product_of_sorted_value_differences(values);
+    }
+
+    fn part2(&self, values: &Vec<usize>) -> ResultHashMap {
+        return count_valid_joltage_paths(values);
+    }
+
+    fn alternatives(&self, part: usize) -> Vec<(&'static str, Solver<Day10>)> {
+        return match part {
+            2 => vec!(("backward", count_valid_joltage_paths_backward as Solver<Day10>)),
+            _ => Vec::new(),
+        };
+    }
+}
+
+fn product_of_sorted_value_differences(values: &Vec<usize>) -> ResultHashMap {
+    let mut one_diffs: usize = 0;
+    let mut three_diffs: usize = 0;
     for i in 1..values.len() {
         match values[i] - values[i - 1] {
             1 => one_diffs += 1,
@@ -32,33 +69,67 @@ fn product_of_sorted_value_differences(args: &[String]) -> ResultHashMap {
     }
 
     return Ok(
-        [(String::from("product of joltage differences"), one_diffs * three_diffs)]
+        [(String::from("product of joltage differences"), Answer::from(one_diffs * three_diffs))]
         .iter()
         .cloned()
         .collect()
     );
 }
 
-fn count_valid_joltage_paths(args: &[String]) -> ResultHashMap {
-    let raw_lines = file_lines_to_string_vec(&args[0])?;
+fn count_valid_joltage_paths(values: &Vec<usize>) -> ResultHashMap {
+    let count = count_valid_paths(values);
+
+    return Ok(
+        [(String::from("valid joltage paths"), Answer::from(count))]
+        .iter()
+        .cloned()
+        .collect()
+    );
+}
 
-    let mut values: Vec<usize> = raw_lines.iter()
-        .map(|l| l.parse().unwrap())
-        .collect();
-    values.sort();
-    values.insert(0, 0);
-    values.push(values.last().unwrap() + 3);
+// Reference for part 2: counts the ways from each adapter on to the device, starting at the device.
+fn count_valid_joltage_paths_backward(_day: &Day10, values: &Vec<usize>) -> ResultHashMap {
+    let mut path_counts = vec![num_bigint::BigUint::from(0u32); values.len()];
+    if let Some(last) = path_counts.last_mut() {
+        *last = num_bigint::BigUint::from(1u32);
+    }
 
-    let count = count_valid_paths(&values);
+    for i in (0..values.len().saturating_sub(1)).rev() {
+        let mut count = num_bigint::BigUint::from(0u32);
+        for j in i + 1..values.len() {
+            if values[j] - values[i] > 3 {
+                break;
+            }
+            count += &path_counts[j];
+        }
+        path_counts[i] = count;
+    }
 
     return Ok(
-        [(String::from("valid joltage paths"), count)]
+        [(String::from("valid joltage paths"), Answer::from(path_counts.first().cloned().unwrap_or_default()))]
         .iter()
         .cloned()
         .collect()
     );
 }
 
+fn count_valid_paths(joltage_adapters: &Vec<usize>) -> num_bigint::BigUint {
+    let mut path_counts = vec![num_bigint::BigUint::from(0u32); joltage_adapters.len()];
+    path_counts[0] = num_bigint::BigUint::from(1u32);
+
+    for i in 1..joltage_adapters.len() {
+        for j in (0..i).rev() {
+            if joltage_adapters[i] - joltage_adapters[j] > 3 {
+                break;
+            }
+
+            path_counts[i] = &path_counts[i] + &path_counts[j];
+        }
+    }
+
+    return path_counts.pop().unwrap_or_default();
+}
+
 //fn count_valid_paths(joltage_adapters: &Vec<usize>, current_index: usize, mut total_count: usize) -> usize {
 //let current_adapter_joltage = joltage_adapters[current_index];
 //let valid_children_indices: Vec<usize> = joltage_adapters.iter().enumerate()
@@ -115,169 +186,16 @@ fn count_valid_joltage_paths(args: &[String]) -> ResultHashMap {
 mod tests {
     use super::challenge;
 
-    //crate::challenge_tests!(1820, 93727241);
-
-    #[test]
-    fn test_product_of_sorted_value_differences_1() {
-        use std::io::Write;
-
-        let mut file = tempfile::NamedTempFile::new().unwrap();
-
-        let file_lines = [
-            "16",
-            "10",
-            "15",
-            "5",
-            "1",
-            "11",
-            "7",
-            "19",
-            "6",
-            "12",
-            "4",
-        ];
-
-        for line in &file_lines {
-            writeln!(file, "{}", line).unwrap();
-        }
-        let args = vec!(String::from(file.path().to_str().unwrap()));
-
-        let actual =
-            super::product_of_sorted_value_differences(&args).unwrap()["product of joltage differences"];
-
-        assert_eq!(35, actual);
-    }
-
-    #[test]
-    fn test_product_of_sorted_value_differences_2() {
-        use std::io::Write;
-
-        let mut file = tempfile::NamedTempFile::new().unwrap();
-
-        let file_lines = [
-            "28",
-            "33",
-            "18",
-            "42",
-            "31",
-            "14",
-            "46",
-            "20",
-            "48",
-            "47",
-            "24",
-            "23",
-            "49",
-            "45",
-            "19",
-            "38",
-            "39",
-            "11",
-            "1",
-            "32",
-            "25",
-            "35",
-            "8",
-            "17",
-            "7",
-            "9",
-            "4",
-            "2",
-            "34",
-            "10",
-            "3",
-            ];
-
-        for line in &file_lines {
-            writeln!(file, "{}", line).unwrap();
-        }
-        let args = vec!(String::from(file.path().to_str().unwrap()));
-
-        let actual =
-            super::product_of_sorted_value_differences(&args).unwrap()["product of joltage differences"];
-
-        assert_eq!(220, actual);
-    }
-
-    #[test]
-    fn test_count_valid_joltage_paths_1() {
-        use std::io::Write;
-
-        let mut file = tempfile::NamedTempFile::new().unwrap();
-
-        let file_lines = [
-            "16",
-            "10",
-            "15",
-            "5",
-            "1",
-            "11",
-            "7",
-            "19",
-            "6",
-            "12",
-            "4",
-        ];
-
-        for line in &file_lines {
-            writeln!(file, "{}", line).unwrap();
-        }
-        let args = vec!(String::from(file.path().to_str().unwrap()));
-
-        let actual =
-            super::count_valid_joltage_paths(&args).unwrap()["product of joltage differences"];
-
-        assert_eq!(8, actual);
+    crate::challenge_tests!(1820, 3454189699072);
+
+    crate::challenge_examples! {
+        example_1: "resources/examples/day10/example_1.txt" => {
+            1: { "product of joltage differences": 35 },
+            2: { "valid joltage paths": 8 },
+        },
+        example_2: "resources/examples/day10/example_2.txt" => {
+            1: { "product of joltage differences": 220 },
+            2: { "valid joltage paths": 19208 },
+        },
     }
-
-    //#[test]
-    //fn test_count_valid_joltage_paths_2() {
-        //use std::io::Write;
-
-        //let mut file = tempfile::NamedTempFile::new().unwrap();
-
-        //let file_lines = [
-            //"28",
-            //"33",
-            //"18",
-            //"42",
-            //"31",
-            //"14",
-            //"46",
-            //"20",
-            //"48",
-            //"47",
-            //"24",
-            //"23",
-            //"49",
-            //"45",
-            //"19",
-            //"38",
-            //"39",
-            //"11",
-            //"1",
-            //"32",
-            //"25",
-            //"35",
-            //"8",
-            //"17",
-            //"7",
-            //"9",
-            //"4",
-            //"2",
-            //"34",
-            //"10",
-            //"3",
-            //];
-
-        //for line in &file_lines {
-            //writeln!(file, "{}", line).unwrap();
-        //}
-        //let args = vec!(String::from(file.path().to_str().unwrap()));
-
-        //let actual =
-            //super::count_valid_joltage_paths(&args).unwrap()["valid joltage paths"];
-
-        //assert_eq!(19208, actual);
-    //}
 }
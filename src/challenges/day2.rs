@@ -1,36 +1,56 @@
 use crate::challenges::Challenge;
+use crate::challenges::ChallengeMetadata;
+use crate::challenges::DynChallenge;
+use crate::Answer;
+use crate::InputError;
+use crate::PuzzleInput;
 use crate::ResultHashMap;
-use crate::file_lines_to_string_vec;
 use crate::PasswordDatabase;
 
-pub fn challenge() -> Challenge {
-    return Challenge::new(
-        valid_passwords_by_charater_count,
-        valid_passwords_by_character_position,
-        String::from("resources/password_database.txt"),
-    );
+pub struct Day2;
+
+pub fn challenge() -> Box<dyn DynChallenge> {
+    return Box::new(Day2);
 }
 
-fn valid_passwords_by_charater_count(args: &[String]) -> ResultHashMap {
-    let raw_lines = file_lines_to_string_vec(&args[0])?;
+impl Challenge for Day2 {
+    type Input = PasswordDatabase;
+
+    fn get_metadata(&self) -> ChallengeMetadata {
+        return ChallengeMetadata {
+            title: "Password Philosophy",
+            puzzle_name: "password database",
+            input_description: "One \"min-max char: password\" policy and password per line",
+            parts: &[1, 2],
+            default_filepath: "resources/password_database.txt",
+        };
+    }
 
-    let mut password_database = PasswordDatabase::new(&raw_lines)?;
+    fn parse(&self, input: &PuzzleInput) -> Result<PasswordDatabase, InputError> {
+        return PasswordDatabase::new(&input.content_lines().to_vec());
+    }
 
+    fn part1(&self, password_database: &PasswordDatabase) -> ResultHashMap {
+        return valid_passwords_by_charater_count(password_database);
+    }
+
+    fn part2(&self, password_database: &PasswordDatabase) -> ResultHashMap {
+        return valid_passwords_by_character_position(password_database);
+    }
+}
+
+fn valid_passwords_by_charater_count(password_database: &PasswordDatabase) -> ResultHashMap {
     return Ok(
-        [(String::from("valid password count"), password_database.valid_passwords_by_character_count())]
+        [(String::from("valid password count"), Answer::from(password_database.valid_passwords_by_character_count()))]
         .iter()
         .cloned()
         .collect()
     );
 }
 
-fn valid_passwords_by_character_position(args: &[String]) -> ResultHashMap {
-    let raw_lines = file_lines_to_string_vec(&args[0])?;
-
-    let mut password_database = PasswordDatabase::new(&raw_lines)?;
-
+fn valid_passwords_by_character_position(password_database: &PasswordDatabase) -> ResultHashMap {
     return Ok(
-        [(String::from("valid password count"), password_database.valid_passwords_by_character_position())]
+        [(String::from("valid password count"), Answer::from(password_database.valid_passwords_by_character_position()))]
         .iter()
         .cloned()
         .collect()
@@ -42,4 +62,11 @@ mod tests {
     use super::challenge;
 
     crate::challenge_tests!(410, 694);
+
+    crate::challenge_examples! {
+        example: "resources/examples/day2/example.txt" => {
+            1: { "valid password count": 2 },
+            2: { "valid password count": 1 },
+        },
+    }
 }
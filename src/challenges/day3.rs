@@ -1,48 +1,133 @@
 use crate::challenges::Challenge;
+use crate::challenges::ChallengeMetadata;
+use crate::challenges::DynChallenge;
+use crate::challenges::unknown_param;
+use crate::Answer;
+use crate::ErrorKind;
+use crate::InputError;
+use crate::PuzzleInput;
 use crate::ResultHashMap;
-use crate::file_lines_to_string_vec;
 use crate::MovementPath;
 use crate::SlopeMap;
 
-pub fn challenge() -> Challenge {
-    return Challenge::new(
-        trees_hit_on_single_traversal,
-        trees_hit_on_multiple_traversals_product,
-        String::from("resources/slope_map.txt"),
-    );
+pub struct Day3 {
+    slope: (i64, i64),
+    slopes: Vec<(i64, i64)>,
+}
+
+impl Day3 {
+    pub fn new() -> Day3 {
+        return Day3 {
+            slope: (3, 1),
+            slopes: vec!((1, 1), (3, 1), (5, 1), (7, 1), (1, 2)),
+        };
+    }
+}
+
+impl Default for Day3 {
+    fn default() -> Day3 {
+        return Day3::new();
+    }
+}
+
+pub fn challenge() -> Box<dyn DynChallenge> {
+    return Box::new(Day3::new());
+}
+
+impl Challenge for Day3 {
+    type Input = SlopeMap;
+
+    fn get_metadata(&self) -> ChallengeMetadata {
+        return ChallengeMetadata {
+            title: "Toboggan Trajectory",
+            puzzle_name: "slope map",
+            input_description: "Rows of '.' open squares and '#' trees, repeating to the right",
+            parts: &[1, 2],
+            default_filepath: "resources/slope_map.txt",
+        };
+    }
+
+    fn parse(&self, input: &PuzzleInput) -> Result<SlopeMap, InputError> {
+        return SlopeMap::new(&input.content_lines().to_vec());
+    }
+
+    fn part1(&self, slope_map: &SlopeMap) -> ResultHashMap {
+        return trees_hit_on_single_traversal(slope_map, self.slope);
+    }
+
+    fn part2(&self, slope_map: &SlopeMap) -> ResultHashMap {
+        return trees_hit_on_multiple_traversals_product(slope_map, &self.slopes);
+    }
+
+    fn get_params(&self) -> Vec<(String, String)> {
+        return vec!(
+            (String::from("slope"), format_slope(self.slope)),
+            (String::from("slopes"), self.slopes.iter().map(|s| format_slope(*s)).collect::<Vec<String>>().join(",")),
+        );
+    }
+
+    fn set_param(&mut self, name: &str, value: &str) -> Result<(), InputError> {
+        match name {
+            "slope" => self.slope = parse_slope(value)?,
+            "slopes" => self.slopes = value.split(',').map(parse_slope).collect::<Result<Vec<(i64, i64)>, InputError>>()?,
+            _ => return Err(unknown_param(name)),
+        }
+
+        return Ok(());
+    }
 }
 
-fn trees_hit_on_single_traversal(args: &[String]) -> ResultHashMap {
-    let raw_lines = file_lines_to_string_vec(&args[0])?;
+// Slopes are written as "right:down", e.g. "3:1".
+fn parse_slope(value: &str) -> Result<(i64, i64), InputError> {
+    let parts: Vec<&str> = value.trim().split(':').collect();
+    if parts.len() != 2 {
+        return Err(bad_slope(value));
+    }
+
+    let horizontal = parts[0].parse::<i64>().map_err(|e| bad_slope(value).with_source(e))?;
+    let downward = parts[1].parse::<i64>().map_err(|e| bad_slope(value).with_source(e))?;
+    if horizontal < 0 || downward < 1 {
+        return Err(bad_slope(value));
+    }
+
+    return Ok((horizontal, downward));
+}
+
+fn bad_slope(value: &str) -> InputError {
+    return InputError::new(ErrorKind::InvalidParameter, "expected a slope written as right:down").with_text(value);
+}
 
-    let slope_map = SlopeMap::new(&raw_lines);
+fn format_slope(slope: (i64, i64)) -> String {
+    return format!("{}:{}", slope.0, slope.1);
+}
 
-    let movement_path = MovementPath::new(3, 1);
+fn trees_hit_on_single_traversal(slope_map: &SlopeMap, slope: (i64, i64)) -> ResultHashMap {
+    let movement_path = MovementPath::new(slope.0, slope.1);
     return Ok(
-        [(String::from("trees hit"), slope_map.count_trees_on_traversal(&movement_path))]
+        [(String::from("trees hit"), Answer::from(slope_map.count_trees_on_traversal(&movement_path)))]
         .iter()
         .cloned()
         .collect()
     );
 }
 
-fn trees_hit_on_multiple_traversals_product(args: &[String]) -> ResultHashMap {
-    let raw_lines = file_lines_to_string_vec(&args[0])?;
-
-    let slope_map = SlopeMap::new(&raw_lines);
-    let movement_paths = vec!(
-        MovementPath::new(1, 1),
-        MovementPath::new(3, 1),
-        MovementPath::new(5, 1),
-        MovementPath::new(7, 1),
-        MovementPath::new(1, 2),
-    );
-    let trees_hit_product = movement_paths.iter()
-        .map(|p| slope_map.count_trees_on_traversal(&p))
-        .fold(1, |acc, x| acc * x);
+fn trees_hit_on_multiple_traversals_product(slope_map: &SlopeMap, slopes: &Vec<(i64, i64)>) -> ResultHashMap {
+    let movement_paths: Vec<MovementPath> = slopes.iter()
+        .map(|s| MovementPath::new(s.0, s.1))
+        .collect();
+    let mut trees_hit_product: usize = 1;
+    for movement_path in movement_paths.iter() {
+        trees_hit_product = match trees_hit_product.checked_mul(slope_map.count_trees_on_traversal(movement_path)) {
+            Some(product) => product,
+            None => return Err(
+                InputError::new(ErrorKind::InvalidParameter, "trees hit product overflows for these slopes")
+                    .with_text(&slopes.iter().map(|s| format_slope(*s)).collect::<Vec<String>>().join(","))
+            ),
+        };
+    }
 
     return Ok(
-        [(String::from("trees hit"), trees_hit_product)]
+        [(String::from("trees hit"), Answer::from(trees_hit_product))]
         .iter()
         .cloned()
         .collect()
@@ -52,6 +137,42 @@ fn trees_hit_on_multiple_traversals_product(args: &[String]) -> ResultHashMap {
 #[cfg(test)]
 mod tests {
     use super::challenge;
+    use super::trees_hit_on_multiple_traversals_product;
+    use crate::ErrorKind;
+    use crate::SlopeMap;
 
     crate::challenge_tests!(294, 5774564250);
+
+    crate::challenge_examples! {
+        example: "resources/examples/day3/example.txt" => {
+            1: { "trees hit": 7 },
+            2: { "trees hit": 336 },
+        },
+    }
+
+    #[test]
+    fn slope_params() {
+        let mut challenge = challenge();
+        challenge.set_params(&[
+            (String::from("slope"), String::from("1:2")),
+            (String::from("slopes"), String::from("1:1,7:1")),
+        ]).unwrap();
+
+        assert_eq!(
+            vec!(
+                (String::from("slope"), String::from("1:2")),
+                (String::from("slopes"), String::from("1:1,7:1")),
+            ),
+            challenge.get_params()
+        );
+        assert!(challenge.set_params(&[(String::from("slope"), String::from("3:0"))]).is_err());
+        assert!(challenge.set_params(&[(String::from("slope"), String::from("3"))]).is_err());
+    }
+
+    #[test]
+    fn overflowing_product_is_an_error() {
+        let slope_map = SlopeMap::new(&vec![String::from("#"); 100]).unwrap();
+        let error = trees_hit_on_multiple_traversals_product(&slope_map, &vec![(1, 1); 10]).unwrap_err();
+        assert_eq!(ErrorKind::InvalidParameter, error.kind());
+    }
 }
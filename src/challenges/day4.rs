@@ -1,64 +1,76 @@
 use crate::challenges::Challenge;
+use crate::challenges::ChallengeMetadata;
+use crate::challenges::DynChallenge;
+use crate::Answer;
+use crate::InputError;
+use crate::PuzzleInput;
+use crate::input::Group;
 use crate::ResultHashMap;
-use crate::file_lines_to_string_vec;
 use crate::Passport;
 
-pub fn challenge() -> Challenge {
-    return Challenge::new(
-        valid_passports_without_validations,
-        valid_passports_with_validations,
-        String::from("resources/passport_database.txt"),
-    );
+pub struct Day4;
+
+pub fn challenge() -> Box<dyn DynChallenge> {
+    return Box::new(Day4);
 }
 
-fn valid_passports_without_validations(args: &[String]) -> ResultHashMap {
-    let raw_lines = file_lines_to_string_vec(&args[0])?;
-
-    let mut passports: Vec<Passport> = Vec::new();
-    let mut key_value_row: String = String::from("");
-    for line in raw_lines {
-        if line == "" {
-            passports.push(Passport::new(&key_value_row.trim())?);
-            key_value_row = String::from("");
-        } else {
-            key_value_row.push_str(" ");
-            key_value_row.push_str(&line);
-        }
+impl Challenge for Day4 {
+    type Input = Vec<Passport>;
+
+    fn get_metadata(&self) -> ChallengeMetadata {
+        return ChallengeMetadata {
+            title: "Passport Processing",
+            puzzle_name: "passport database",
+            input_description: "key:value passport fields, with passports separated by blank lines",
+            parts: &[1, 2],
+            default_filepath: "resources/passport_database.txt",
+        };
     }
 
-    if key_value_row != "" {
-        passports.push(Passport::new(&key_value_row.trim())?);
+    fn parse(&self, input: &PuzzleInput) -> Result<Vec<Passport>, InputError> {
+        return input.groups().iter().map(parse_passport).collect();
     }
 
+    fn part1(&self, passports: &Vec<Passport>) -> ResultHashMap {
+        return valid_passports_without_validations(passports);
+    }
+
+    fn part2(&self, passports: &Vec<Passport>) -> ResultHashMap {
+        return valid_passports_with_validations(passports);
+    }
+}
+
+// A passport spans several lines, so an error's column in the joined row is mapped back to the
+// line it came from.
+fn parse_passport(group: &Group) -> Result<Passport, InputError> {
+    let key_value_row = group.lines.join(" ");
+
+    return Passport::new(&key_value_row).map_err(|e| {
+        let mut column = e.column().unwrap_or(1);
+        for (i, line) in group.lines.iter().enumerate() {
+            let width = line.chars().count();
+            if column <= width {
+                return e.with_line_text(line).at_line(group.first_line + i).at_column(column);
+            }
+            column -= width + 1;
+        }
+
+        return e;
+    });
+}
+
+fn valid_passports_without_validations(passports: &Vec<Passport>) -> ResultHashMap {
     return Ok(
-        [(String::from("valid passports"), passports.iter().filter(|p| p.is_valid(false)).count())]
+        [(String::from("valid passports"), Answer::from(passports.iter().filter(|p| p.is_valid(false)).count()))]
         .iter()
         .cloned()
         .collect()
     );
 }
 
-fn valid_passports_with_validations(args: &[String]) -> ResultHashMap {
-    let raw_lines = file_lines_to_string_vec(&args[0])?;
-
-    let mut passports: Vec<Passport> = Vec::new();
-    let mut key_value_row: String = String::from("");
-    for line in raw_lines {
-        if line == "" {
-            passports.push(Passport::new(&key_value_row.trim())?);
-            key_value_row = String::from("");
-        } else {
-            key_value_row.push_str(" ");
-            key_value_row.push_str(&line);
-        }
-    }
-
-    if key_value_row != "" {
-        passports.push(Passport::new(&key_value_row.trim())?);
-    }
-
+fn valid_passports_with_validations(passports: &Vec<Passport>) -> ResultHashMap {
     return Ok(
-        [(String::from("valid passports"), passports.iter().filter(|p| p.is_valid(true)).count())]
+        [(String::from("valid passports"), Answer::from(passports.iter().filter(|p| p.is_valid(true)).count()))]
         .iter()
         .cloned()
         .collect()
@@ -70,4 +82,28 @@ mod tests {
     use super::challenge;
 
     crate::challenge_tests!(204, 179);
+
+    crate::challenge_examples! {
+        example: "resources/examples/day4/example.txt" => {
+            1: { "valid passports": 2 },
+        },
+        invalid_passports: "resources/examples/day4/invalid_passports.txt" => {
+            2: { "valid passports": 0 },
+        },
+        valid_passports: "resources/examples/day4/valid_passports.txt" => {
+            2: { "valid passports": 4 },
+        },
+    }
+
+    #[test]
+    fn malformed_pair_is_located_in_its_source_line() {
+        let input = "ecl:gry pid:860033327\n\nhcl:#cfa07d\neyr:2024 byr1937";
+
+        let error = challenge().run_str(1, input).unwrap_err();
+
+        assert_eq!(crate::ErrorKind::MalformedPassport, error.kind());
+        assert_eq!(Some(4), error.line());
+        assert_eq!(Some(10), error.column());
+        assert_eq!(Some("byr1937"), error.text());
+    }
 }
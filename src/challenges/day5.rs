@@ -1,59 +1,76 @@
 use crate::challenges::Challenge;
+use crate::challenges::ChallengeMetadata;
+use crate::challenges::DynChallenge;
+use crate::Answer;
+use crate::ErrorKind;
 use crate::InputError;
+use crate::PuzzleInput;
 use crate::ResultHashMap;
-use crate::file_lines_to_string_vec;
-use crate::binary_partitioner;
-
-pub fn challenge() -> Challenge {
-    return Challenge::new(
-        max_seat_id,
-        my_seat_id,
-        String::from("resources/binary_partitioning_seats.txt"),
-    );
+use crate::binary_partitioner::BoardingPass;
+
+pub struct Day5;
+
+pub fn challenge() -> Box<dyn DynChallenge> {
+    return Box::new(Day5);
 }
 
-fn max_seat_id(args: &[String]) -> ResultHashMap {
-    let raw_lines = file_lines_to_string_vec(&args[0])?;
+impl Challenge for Day5 {
+    type Input = Vec<usize>;
+
+    fn get_metadata(&self) -> ChallengeMetadata {
+        return ChallengeMetadata {
+            title: "Binary Boarding",
+            puzzle_name: "binary partitioning seats",
+            input_description: "One ten-character F/B/L/R boarding pass per line",
+            parts: &[1, 2],
+            default_filepath: "resources/binary_partitioning_seats.txt",
+        };
+    }
+
+    fn parse(&self, input: &PuzzleInput) -> Result<Vec<usize>, InputError> {
+        let mut seat_ids = Vec::new();
+        for (i, line) in input.content_lines().iter().enumerate() {
+            let boarding_pass = line.parse::<BoardingPass>().map_err(|e| e.with_line_text(line).at_line(i + 1))?;
+            seat_ids.push(boarding_pass.seat_id());
+        }
+
+        return Ok(seat_ids);
+    }
+
+    fn part1(&self, seat_ids: &Vec<usize>) -> ResultHashMap {
+        return max_seat_id(seat_ids);
+    }
+
+    fn part2(&self, seat_ids: &Vec<usize>) -> ResultHashMap {
+        return my_seat_id(seat_ids);
+    }
+}
 
-    let max_seat_id = raw_lines.iter()
-        .map(|l| binary_partitioner::partition(&l).unwrap())
-        .map(|hm| hm["row"] * 8 + hm["column"])
-        .max();
+fn max_seat_id(seat_ids: &Vec<usize>) -> ResultHashMap {
+    let max_seat_id = seat_ids.iter().max();
 
     return match max_seat_id {
         Some(id) => Ok(
-            [(String::from("max seat id"), id)]
+            [(String::from("max seat id"), Answer::from(*id))]
             .iter()
             .cloned()
             .collect()
         ),
-        None => Err(InputError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, "Bad input"))),
+        None => Err(InputError::new(ErrorKind::EmptyInput, "no boarding passes to search")),
     }
 }
 
-fn my_seat_id(args: &[String]) -> ResultHashMap {
-    let raw_lines = file_lines_to_string_vec(&args[0])?;
-
-    let wrapped_seat_ids = raw_lines.iter()
-        .map(|l| binary_partitioner::partition(&l).unwrap())
-        .map(|hm| hm["row"] * 8 + hm["column"]);
-
-    let mut sorted_seat_ids = Vec::new();
-    for seat_id in wrapped_seat_ids {
-        sorted_seat_ids.push(seat_id);
-    }
+fn my_seat_id(seat_ids: &Vec<usize>) -> ResultHashMap {
+    let mut sorted_seat_ids = seat_ids.clone();
     sorted_seat_ids.sort();
 
-    let mut my_seat_id = 0;
-    for i in 0..sorted_seat_ids.len() {
-        if sorted_seat_ids[i + 1] - sorted_seat_ids[i] == 2 {
-            my_seat_id = sorted_seat_ids[i] + 1;
-            break;
-        }
-    }
+    let my_seat_id = match sorted_seat_ids.windows(2).find(|w| w[1] - w[0] == 2) {
+        Some(w) => w[0] + 1,
+        None => return Err(InputError::new(ErrorKind::NoSolution, "no single empty seat between two taken seats")),
+    };
 
     return Ok(
-        [(String::from("max seat id"), my_seat_id)]
+        [(String::from("max seat id"), Answer::from(my_seat_id))]
         .iter()
         .cloned()
         .collect()
@@ -65,4 +82,10 @@ mod tests {
     use super::challenge;
 
     crate::challenge_tests!(838, 714);
+
+    crate::challenge_examples! {
+        example: "resources/examples/day5/example.txt" => {
+            1: { "max seat id": 820 },
+        },
+    }
 }
@@ -1,63 +1,72 @@
 use crate::challenges::Challenge;
+use crate::challenges::ChallengeMetadata;
+use crate::challenges::DynChallenge;
+use crate::Answer;
+use crate::InputError;
+use crate::PuzzleInput;
 use crate::ResultHashMap;
-use crate::file_lines_to_string_vec;
 
-pub fn challenge() -> Challenge {
-    return Challenge::new(
-        sum_of_any_group_answered_questions,
-        sum_of_all_group_answered_questions,
-        String::from("resources/customs_answers.txt"),
-    );
+pub struct Day6;
+
+pub fn challenge() -> Box<dyn DynChallenge> {
+    return Box::new(Day6);
 }
 
-fn sum_of_any_group_answered_questions(args: &[String]) -> ResultHashMap {
-    let raw_lines = file_lines_to_string_vec(&args[0])?;
+impl Challenge for Day6 {
+    type Input = Vec<Vec<String>>;
+
+    fn get_metadata(&self) -> ChallengeMetadata {
+        return ChallengeMetadata {
+            title: "Custom Customs",
+            puzzle_name: "customs answers",
+            input_description: "One person's yes answers per line, with groups separated by blank lines",
+            parts: &[1, 2],
+            default_filepath: "resources/customs_answers.txt",
+        };
+    }
 
-    let mut group_answered_questions = 0;
-    let mut group_answers: String = String::from("");
-    for line in raw_lines {
-        if line == "" {
-            group_answered_questions += string_to_char_set(&group_answers).len();
+    fn parse(&self, input: &PuzzleInput) -> Result<Vec<Vec<String>>, InputError> {
+        return Ok(
+            input.groups().iter()
+                .map(|g| g.lines.iter().map(|l| l.trim().to_string()).collect())
+                .collect()
+        );
+    }
 
-            group_answers = String::from("");
-        } else {
-            group_answers.push_str(line.trim());
-        }
+    fn part1(&self, groups: &Vec<Vec<String>>) -> ResultHashMap {
+        return sum_of_any_group_answered_questions(groups);
     }
 
-    if group_answers != "" {
-        group_answered_questions += string_to_char_set(&group_answers).len();
+    fn part2(&self, groups: &Vec<Vec<String>>) -> ResultHashMap {
+        return sum_of_all_group_answered_questions(groups);
     }
+}
+
+fn sum_of_any_group_answered_questions(groups: &Vec<Vec<String>>) -> ResultHashMap {
+    let group_answered_questions: usize = groups.iter()
+        .map(|g| string_to_char_set(&g.concat()).len())
+        .sum();
 
     return Ok(
-        [(String::from("total group-answered questions"), group_answered_questions)]
+        [(String::from("total group-answered questions"), Answer::from(group_answered_questions))]
         .iter()
         .cloned()
         .collect()
     );
 }
 
-fn sum_of_all_group_answered_questions(args: &[String]) -> ResultHashMap {
-    let raw_lines = file_lines_to_string_vec(&args[0])?;
+fn sum_of_all_group_answered_questions(groups: &Vec<Vec<String>>) -> ResultHashMap {
+    let mut group_answered_questions: usize = 0;
+    for group in groups.iter().filter(|g| !g.is_empty()) {
+        let group_answer_sets: Vec<std::collections::HashSet<char>> = group.iter()
+            .map(string_to_char_set)
+            .collect();
 
-    let mut group_answered_questions = 0;
-    let mut group_answer_sets: Vec<std::collections::HashSet<char>> = Vec::new();
-    for line in raw_lines {
-        if line == "" {
-            group_answered_questions += intersection_of_sets(&group_answer_sets).len();
-
-            group_answer_sets = Vec::new();
-        } else {
-            group_answer_sets.push(string_to_char_set(&line));
-        }
-    }
-
-    if group_answer_sets.len() > 0 {
         group_answered_questions += intersection_of_sets(&group_answer_sets).len();
     }
 
     return Ok(
-        [(String::from("total group-answered questions"), group_answered_questions)]
+        [(String::from("total group-answered questions"), Answer::from(group_answered_questions))]
         .iter()
         .cloned()
         .collect()
@@ -77,10 +86,10 @@ fn intersection_of_sets<T>(sets: &Vec<std::collections::HashSet<T>>) -> std::col
     let mut intersection: std::collections::HashSet<T> = sets[0].clone();
     for item in &sets[0] {
         let mut found_in_set = sets[1..].iter()
-            .map(|s| s.contains(&item));
+            .map(|s| s.contains(item));
 
         if found_in_set.any(|found| !found) {
-            intersection.remove(&item);
+            intersection.remove(item);
         }
     }
 
@@ -93,63 +102,10 @@ mod tests {
 
     crate::challenge_tests!(6726, 3316);
 
-    #[test]
-    fn sum_of_any_group_answered_questions() {
-        use std::io::Write;
-
-        let mut file = tempfile::NamedTempFile::new().unwrap();
-        writeln!(file, "abc").unwrap();
-        writeln!(file, "").unwrap();
-        writeln!(file, "a").unwrap();
-        writeln!(file, "b").unwrap();
-        writeln!(file, "c").unwrap();
-        writeln!(file, "").unwrap();
-        writeln!(file, "ab").unwrap();
-        writeln!(file, "ac").unwrap();
-        writeln!(file, "").unwrap();
-        writeln!(file, "a").unwrap();
-        writeln!(file, "a").unwrap();
-        writeln!(file, "a").unwrap();
-        writeln!(file, "a").unwrap();
-        writeln!(file, "").unwrap();
-        writeln!(file, "b").unwrap();
-
-        let args = vec!(String::from(file.path().to_str().unwrap()));
-        let actual =
-            super::sum_of_any_group_answered_questions(&args)
-            .unwrap()
-            ["total group-answered questions"];
-
-        assert_eq!(11, actual);
-    }
-
-    #[test]
-    fn sum_of_all_group_answered_questions() {
-        use std::io::Write;
-
-        let mut file = tempfile::NamedTempFile::new().unwrap();
-        writeln!(file, "abc").unwrap();
-        writeln!(file, "").unwrap();
-        writeln!(file, "a").unwrap();
-        writeln!(file, "b").unwrap();
-        writeln!(file, "c").unwrap();
-        writeln!(file, "").unwrap();
-        writeln!(file, "ab").unwrap();
-        writeln!(file, "ac").unwrap();
-        writeln!(file, "").unwrap();
-        writeln!(file, "a").unwrap();
-        writeln!(file, "a").unwrap();
-        writeln!(file, "a").unwrap();
-        writeln!(file, "a").unwrap();
-        writeln!(file, "").unwrap();
-        writeln!(file, "b").unwrap();
-
-        let args = vec!(String::from(file.path().to_str().unwrap()));
-        let actual =
-            super::sum_of_all_group_answered_questions(&args)
-            .unwrap()
-            ["total group-answered questions"];
-
-        assert_eq!(6, actual);
+    crate::challenge_examples! {
+        example: "resources/examples/day6/example.txt" => {
+            1: { "total group-answered questions": 11 },
+            2: { "total group-answered questions": 6 },
+        },
     }
 }
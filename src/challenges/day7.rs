@@ -1,29 +1,82 @@
 use crate::challenges::Challenge;
+use crate::challenges::ChallengeMetadata;
+use crate::challenges::DynChallenge;
+use crate::challenges::unknown_param;
+use crate::Answer;
+use crate::InputError;
+use crate::PuzzleInput;
 use crate::ResultHashMap;
-use crate::file_lines_to_string_vec;
 use crate::DirectedGraph;
+use crate::bag_rule;
 
-pub fn challenge() -> Challenge {
-    return Challenge::new(
-        count_outermost_bags,
-        count_contained_bags,
-        String::from("resources/luggage_rules.txt"),
-    );
+pub struct Day7 {
+    bag: String,
+}
+
+impl Day7 {
+    pub fn new() -> Day7 {
+        return Day7 { bag: String::from("shiny gold") };
+    }
+}
+
+impl Default for Day7 {
+    fn default() -> Day7 {
+        return Day7::new();
+    }
+}
+
+pub fn challenge() -> Box<dyn DynChallenge> {
+    return Box::new(Day7::new());
 }
 
-fn count_outermost_bags(args: &[String]) -> ResultHashMap {
-    let raw_lines = file_lines_to_string_vec(&args[0])?;
+impl Challenge for Day7 {
+    type Input = DirectedGraph;
+
+    fn get_metadata(&self) -> ChallengeMetadata {
+        return ChallengeMetadata {
+            title: "Handy Haversacks",
+            puzzle_name: "luggage rules",
+            input_description: "One \"<colour> bags contain ...\" rule per line",
+            parts: &[1, 2],
+            default_filepath: "resources/luggage_rules.txt",
+        };
+    }
 
-    let graph = build_graph_from_input(&raw_lines);
+    fn parse(&self, input: &PuzzleInput) -> Result<DirectedGraph, InputError> {
+        return bag_rule::build_graph(&input.content_lines().to_vec());
+    }
 
-    let mut outermost_bags = 0;
+    fn part1(&self, graph: &DirectedGraph) -> ResultHashMap {
+        return count_outermost_bags(graph, &self.bag);
+    }
+
+    fn part2(&self, graph: &DirectedGraph) -> ResultHashMap {
+        return count_contained_bags(graph, &self.bag);
+    }
+
+    fn get_params(&self) -> Vec<(String, String)> {
+        return vec!((String::from("bag"), self.bag.clone()));
+    }
+
+    fn set_param(&mut self, name: &str, value: &str) -> Result<(), InputError> {
+        match name {
+            "bag" => self.bag = value.to_string(),
+            _ => return Err(unknown_param(name)),
+        }
+
+        return Ok(());
+    }
+}
+
+fn count_outermost_bags(graph: &DirectedGraph, bag: &str) -> ResultHashMap {
+    let mut outermost_bags: usize = 0;
     for start in graph.get_node_names().iter() {
-        if *start == "shiny gold" {
+        if *start == bag {
             continue;
         }
 
         for n in graph.iter_from_node(start) {
-            if n.0.get_name() == "shiny gold" {
+            if n.0.get_name() == bag {
                 outermost_bags += 1;
                 break;
             }
@@ -31,146 +84,53 @@ fn count_outermost_bags(args: &[String]) -> ResultHashMap {
     }
 
     return Ok(
-        [(String::from("outermost bags"), outermost_bags)]
+        [(String::from("outermost bags"), Answer::from(outermost_bags))]
         .iter()
         .cloned()
         .collect()
     );
 }
 
-fn count_contained_bags(args: &[String]) -> ResultHashMap {
-    let raw_lines = file_lines_to_string_vec(&args[0])?;
-
-    let graph = build_graph_from_input(&raw_lines);
-
-    let start = "shiny gold";
-    let contained_bags = graph.sum_of_path_weight_products(start);
+fn count_contained_bags(graph: &DirectedGraph, bag: &str) -> ResultHashMap {
+    let contained_bags = graph.sum_of_path_weight_products(bag)?;
 
     return Ok(
-        [(String::from("contained bags"), contained_bags as usize)]
+        [(String::from("contained bags"), Answer::from(contained_bags))]
         .iter()
         .cloned()
         .collect()
     );
 }
 
-fn build_graph_from_input(input: &Vec<String>) -> DirectedGraph {
-    let mut graph = DirectedGraph::new();
-    for line in input {
-        let parsed_line = parse_input_line(&line);
-
-        graph.add_node_if_not_exists(parsed_line.0.clone());
-        for edge in parsed_line.1 {
-            graph.add_node_if_not_exists(edge.0.clone());
-            graph.add_edge_by_names(&parsed_line.0, &edge.0, edge.1);
-        }
-    }
-
-    return graph;
-}
-
-fn parse_input_line(line: &str) -> (String, Vec<(String, i64)>) {
-    let from_re = regex::Regex::new(r"^([\w\s]+?) bag").unwrap();
-    let from_node = from_re.captures(line).unwrap().get(1).unwrap().as_str();
-
-    let to_re = regex::Regex::new(r"(?:contain|,) (\d+ [\w\s]+?) bag").unwrap();
-    let mut to_nodes = Vec::new();
-    for m in to_re.captures_iter(line) {
-        let strs: Vec<&str> = m.get(1).unwrap().as_str().splitn(2, " ").collect();
-        let weight = strs[0].parse::<i64>().unwrap();
-
-        to_nodes.push((strs[1].to_string(), weight));
-    }
-
-
-    return (from_node.to_string(), to_nodes);
-}
-
 #[cfg(test)]
 mod tests {
     use super::challenge;
 
     crate::challenge_tests!(192, 12128);
 
-    #[test]
-    fn test_count_outermost_bags() {
-        use std::io::Write;
-
-        let mut file = tempfile::NamedTempFile::new().unwrap();
-
-        let file_lines = [
-            "light red bags contain 1 bright white bag, 2 muted yellow bags.",
-            "dark orange bags contain 3 bright white bags, 4 muted yellow bags.",
-            "bright white bags contain 1 shiny gold bag.",
-            "muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.",
-            "shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.",
-            "dark olive bags contain 3 faded blue bags, 4 dotted black bags.",
-            "vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.",
-            "faded blue bags contain no other bags.",
-            "dotted black bags contain no other bags.",
-        ];
-
-        for line in &file_lines {
-            writeln!(file, "{}", line).unwrap();
-        }
-        let args = vec!(String::from(file.path().to_str().unwrap()));
-        let actual =
-            super::count_outermost_bags(&args).unwrap()["outermost bags"];
-
-        assert_eq!(4, actual);
+    crate::challenge_examples! {
+        example_1: "resources/examples/day7/example_1.txt" => {
+            1: { "outermost bags": 4 },
+            2: { "contained bags": 32 },
+        },
+        example_2: "resources/examples/day7/example_2.txt" => {
+            2: { "contained bags": 126 },
+        },
+        example_1_with_bag_param: "resources/examples/day7/example_1.txt" with { "bag": "dark olive" } => {
+            1: { "outermost bags": 5 },
+            2: { "contained bags": 7 },
+        },
     }
 
     #[test]
-    fn test_count_contained_bags_1() {
-        use std::io::Write;
-
-        let mut file = tempfile::NamedTempFile::new().unwrap();
-
-        let file_lines = [
-            "light red bags contain 1 bright white bag, 2 muted yellow bags.",
-            "dark orange bags contain 3 bright white bags, 4 muted yellow bags.",
-            "bright white bags contain 1 shiny gold bag.",
-            "muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.",
-            "shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.",
-            "dark olive bags contain 3 faded blue bags, 4 dotted black bags.",
-            "vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.",
-            "faded blue bags contain no other bags.",
-            "dotted black bags contain no other bags.",
-        ];
-
-        for line in &file_lines {
-            writeln!(file, "{}", line).unwrap();
-        }
-        let args = vec!(String::from(file.path().to_str().unwrap()));
-        let actual =
-            super::count_contained_bags(&args).unwrap()["contained bags"];
-
-        assert_eq!(32, actual);
-    }
+    fn malformed_rule_is_located() {
+        let input = "faded blue bags contain no other bags.\nlight red bags contain one bright white bag.";
 
-    #[test]
-    fn test_count_contained_bags_2() {
-        use std::io::Write;
-
-        let mut file = tempfile::NamedTempFile::new().unwrap();
-
-        let file_lines = [
-            "shiny gold bags contain 2 dark red bags.",
-            "dark red bags contain 2 dark orange bags.",
-            "dark orange bags contain 2 dark yellow bags.",
-            "dark yellow bags contain 2 dark green bags.",
-            "dark green bags contain 2 dark blue bags.",
-            "dark blue bags contain 2 dark violet bags.",
-            "dark violet bags contain no other bags.",
-        ];
-
-        for line in &file_lines {
-            writeln!(file, "{}", line).unwrap();
-        }
-        let args = vec!(String::from(file.path().to_str().unwrap()));
-        let actual =
-            super::count_contained_bags(&args).unwrap()["contained bags"];
+        let error = challenge().run_str(1, input).unwrap_err();
 
-        assert_eq!(126, actual);
+        assert_eq!(crate::ErrorKind::MalformedBagRule, error.kind());
+        assert_eq!(Some(2), error.line());
+        assert_eq!(Some(24), error.column());
+        assert_eq!(Some("one"), error.text());
     }
 }
@@ -1,38 +1,62 @@
 use crate::challenges::Challenge;
+use crate::challenges::ChallengeMetadata;
+use crate::challenges::DynChallenge;
+use crate::Answer;
+use crate::InputError;
+use crate::PuzzleInput;
 use crate::ResultHashMap;
-use crate::file_lines_to_string_vec;
 use crate::BootCodeComputer;
 
-pub fn challenge() -> Challenge {
-    return Challenge::new(
-        accumulator_value_before_first_loop,
-        accumulator_value_after_swap_completion,
-        String::from("resources/boot_code.txt"),
-    );
+pub struct Day8;
+
+pub fn challenge() -> Box<dyn DynChallenge> {
+    return Box::new(Day8);
 }
 
-fn accumulator_value_before_first_loop(args: &[String]) -> ResultHashMap {
-    let raw_lines = file_lines_to_string_vec(&args[0])?;
+impl Challenge for Day8 {
+    type Input = BootCodeComputer;
+
+    fn get_metadata(&self) -> ChallengeMetadata {
+        return ChallengeMetadata {
+            title: "Handheld Halting",
+            puzzle_name: "boot code",
+            input_description: "One nop/acc/jmp instruction with a signed argument per line",
+            parts: &[1, 2],
+            default_filepath: "resources/boot_code.txt",
+        };
+    }
 
-    let mut computer = BootCodeComputer::new(&raw_lines);
-    computer.run_until_first_loop();
+    fn parse(&self, input: &PuzzleInput) -> Result<BootCodeComputer, InputError> {
+        return BootCodeComputer::new(&input.content_lines().to_vec());
+    }
+
+    fn part1(&self, computer: &BootCodeComputer) -> ResultHashMap {
+        return accumulator_value_before_first_loop(computer);
+    }
+
+    fn part2(&self, computer: &BootCodeComputer) -> ResultHashMap {
+        return accumulator_value_after_swap_completion(computer);
+    }
+}
+
+fn accumulator_value_before_first_loop(computer: &BootCodeComputer) -> ResultHashMap {
+    let mut computer = computer.clone();
+    computer.run_until_first_loop()?;
 
     return Ok(
-        [(String::from("accumulator value"), computer.get_accumulator_value() as usize)]
+        [(String::from("accumulator value"), Answer::from(computer.get_accumulator_value()))]
         .iter()
         .cloned()
         .collect()
     );
 }
 
-fn accumulator_value_after_swap_completion(args: &[String]) -> ResultHashMap {
-    let raw_lines = file_lines_to_string_vec(&args[0])?;
-
-    let mut computer = BootCodeComputer::new(&raw_lines);
-    computer.run_until_complete_with_instruction_swaps();
+fn accumulator_value_after_swap_completion(computer: &BootCodeComputer) -> ResultHashMap {
+    let mut computer = computer.clone();
+    computer.run_until_complete_with_instruction_swaps()?;
 
     return Ok(
-        [(String::from("accumulator value"), computer.get_accumulator_value() as usize)]
+        [(String::from("accumulator value"), Answer::from(computer.get_accumulator_value()))]
         .iter()
         .cloned()
         .collect()
@@ -44,4 +68,11 @@ mod tests {
     use super::challenge;
 
     crate::challenge_tests!(2051, 2304);
+
+    crate::challenge_examples! {
+        example: "resources/examples/day8/example.txt" => {
+            1: { "accumulator value": 5 },
+            2: { "accumulator value": 8 },
+        },
+    }
 }
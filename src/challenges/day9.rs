@@ -1,69 +1,183 @@
 use crate::challenges::Challenge;
+use crate::challenges::ChallengeMetadata;
+use crate::challenges::DynChallenge;
+use crate::challenges::Solver;
+use crate::challenges::parse_param_value;
+use crate::challenges::unknown_param;
+use crate::Answer;
+use crate::ErrorKind;
+use crate::InputError;
+use crate::PuzzleInput;
 use crate::ResultHashMap;
-use crate::file_lines_to_string_vec;
-
-pub fn challenge() -> Challenge {
-    return Challenge::new(
-        first_number_not_sum_of_previous_2,
-        sum_from_contiguous_numbers,
-        String::from("resources/xmas_values.txt"),
-    );
+
+pub struct Day9 {
+    preamble_size: usize,
+    target_value: usize,
+}
+
+impl Day9 {
+    pub fn new() -> Day9 {
+        return Day9 {
+            preamble_size: 25,
+            target_value: 756008079,
+        };
+    }
 }
 
-fn first_number_not_sum_of_previous_2(args: &[String]) -> ResultHashMap {
-    let raw_lines = file_lines_to_string_vec(&args[0])?;
-    let values: Vec<usize> = raw_lines.iter().map(|l| l.parse().unwrap()).collect();
+impl Default for Day9 {
+    fn default() -> Day9 {
+        return Day9::new();
+    }
+}
 
-    let preamble_size = 25;
+pub fn challenge() -> Box<dyn DynChallenge> {
+    return Box::new(Day9::new());
+}
+
+impl Challenge for Day9 {
+    type Input = Vec<usize>;
+
+    fn get_metadata(&self) -> ChallengeMetadata {
+        return ChallengeMetadata {
+            title: "Encoding Error",
+            puzzle_name: "xmas values",
+            input_description: "One XMAS number per line",
+            parts: &[1, 2],
+            default_filepath: "resources/xmas_values.txt",
+        };
+    }
+
+    fn parse(&self, input: &PuzzleInput) -> Result<Vec<usize>, InputError> {
+        return input.integers();
+    }
+
+    fn part1(&self, values: &Vec<usize>) -> ResultHashMap {
+        return first_number_not_sum_of_previous_2(values, self.preamble_size);
+    }
+
+    fn part2(&self, values: &Vec<usize>) -> ResultHashMap {
+        return sum_from_contiguous_numbers(values, self.target_value);
+    }
+
+    fn alternatives(&self, part: usize) -> Vec<(&'static str, Solver<Day9>)> {
+        return match part {
+            1 => vec!(("window set", first_number_not_sum_of_window_set as Solver<Day9>)),
+            2 => vec!(("sliding window", sum_from_sliding_window as Solver<Day9>)),
+            _ => Vec::new(),
+        };
+    }
+
+    fn get_params(&self) -> Vec<(String, String)> {
+        return vec!(
+            (String::from("preamble"), self.preamble_size.to_string()),
+            (String::from("target"), self.target_value.to_string()),
+        );
+    }
+
+    fn set_param(&mut self, name: &str, value: &str) -> Result<(), InputError> {
+        match name {
+            "preamble" => self.preamble_size = parse_param_value(name, value)?,
+            "target" => self.target_value = parse_param_value(name, value)?,
+            _ => return Err(unknown_param(name)),
+        }
 
-    let mut current_index = preamble_size;
-    while current_index < values.len() {
+        return Ok(());
+    }
+}
+
+fn first_number_not_sum_of_previous_2(values: &Vec<usize>, preamble_size: usize) -> ResultHashMap {
+    for current_index in preamble_size..values.len() {
         let candidate_values = &values[current_index - preamble_size..current_index];
         if !values_summing_to_target_exist(candidate_values, values[current_index]) {
-            break;
+            return Ok(
+                [(String::from("target number"), Answer::from(values[current_index]))]
+                .iter()
+                .cloned()
+                .collect()
+            );
         }
-
-        current_index += 1;
     }
 
-    return Ok(
-        [(String::from("target number"), values[current_index])]
-        .iter()
-        .cloned()
-        .collect()
-    );
+    return Err(InputError::new(ErrorKind::NoSolution, "every number after the preamble is a sum of two before it"));
 }
 
-fn sum_from_contiguous_numbers(args: &[String]) -> ResultHashMap {
+fn sum_from_contiguous_numbers(values: &Vec<usize>, target_value: usize) -> ResultHashMap {
     use itertools::Itertools;
 
-    let raw_lines = file_lines_to_string_vec(&args[0])?;
-    let values: Vec<usize> = raw_lines.iter().map(|l| l.parse().unwrap()).collect();
-
-    let target_value = 756008079;
-
-    let mut target_sum = 0;
     for v in values.iter().enumerate() {
         let mut i = v.0 + 1;
         let mut summed_values = vec!(v.1);
-        while summed_values.iter().fold(0, |acc, val| acc + *val) < target_value && i < values.len() {
+        let mut sum = Some(*v.1);
+        while sum.is_some_and(|s| s < target_value) && i < values.len() {
             summed_values.push(&values[i]);
+            sum = sum.and_then(|s| s.checked_add(values[i]));
             i += 1;
         }
 
-        if summed_values.iter().fold(0, |acc, val| acc + *val) == target_value {
-            let min_and_max = summed_values.iter().minmax().into_option().unwrap();
-            target_sum = *min_and_max.0 + *min_and_max.1;
-            break;
+        // The run must hold at least two numbers, so the target on its own does not count.
+        if sum == Some(target_value) && summed_values.len() > 1 {
+            if let Some((min, max)) = summed_values.iter().minmax().into_option() {
+                return Ok(
+                    [(String::from("target number"), Answer::from(**min as u128 + **max as u128))]
+                    .iter()
+                    .cloned()
+                    .collect()
+                );
+            }
+        }
+    }
+
+    return Err(InputError::new(ErrorKind::NoSolution, "no contiguous run of numbers sums to the target"));
+}
+
+// Reference for part 1: a number is valid when the window holds a different value that makes up
+// the difference.
+fn first_number_not_sum_of_window_set(day: &Day9, values: &Vec<usize>) -> ResultHashMap {
+    for current_index in day.preamble_size..values.len() {
+        let window: std::collections::HashSet<usize> = values[current_index - day.preamble_size..current_index].iter().cloned().collect();
+        let target = values[current_index];
+
+        if !window.iter().any(|v| *v <= target && target - v != *v && window.contains(&(target - v))) {
+            return Ok(
+                [(String::from("target number"), Answer::from(target))]
+                .iter()
+                .cloned()
+                .collect()
+            );
+        }
+    }
+
+    return Err(InputError::new(ErrorKind::NoSolution, "every number after the preamble is a sum of two before it"));
+}
+
+// Reference for part 2: a window that grows at its end and shrinks from its start, only counting
+// runs of at least two numbers as the puzzle asks.
+fn sum_from_sliding_window(day: &Day9, values: &Vec<usize>) -> ResultHashMap {
+    let target = day.target_value as u128;
+    let mut start = 0;
+    let mut sum: u128 = 0;
+    for end in 0..values.len() {
+        sum += values[end] as u128;
+        while sum > target && start < end {
+            sum -= values[start] as u128;
+            start += 1;
+        }
+
+        if sum == target && end > start {
+            let run = &values[start..=end];
+            let min = run.iter().min().copied().unwrap_or_default();
+            let max = run.iter().max().copied().unwrap_or_default();
+
+            return Ok(
+                [(String::from("target number"), Answer::from(min as u128 + max as u128))]
+                .iter()
+                .cloned()
+                .collect()
+            );
         }
     }
 
-    return Ok(
-        [(String::from("target number"), target_sum)]
-        .iter()
-        .cloned()
-        .collect()
-    );
+    return Err(InputError::new(ErrorKind::NoSolution, "no contiguous run of numbers sums to the target"));
 }
 
 fn values_summing_to_target_exist(values: &[usize], target: usize) -> bool {
@@ -72,7 +186,7 @@ fn values_summing_to_target_exist(values: &[usize], target: usize) -> bool {
     let combinations = values.iter().cartesian_product(values.iter());
 
     for c in combinations {
-        if c.0 + c.1 == target && c.0 != c.1 {
+        if c.0.checked_add(*c.1) == Some(target) && c.0 != c.1 {
             return true;
         }
     }
@@ -85,4 +199,11 @@ mod tests {
     use super::challenge;
 
     crate::challenge_tests!(756008079, 93727241);
+
+    crate::challenge_examples! {
+        example: "resources/examples/day9/example.txt" with { "preamble": "5", "target": "127" } => {
+            1: { "target number": 127 },
+            2: { "target number": 62 },
+        },
+    }
 }
@@ -0,0 +1,82 @@
+use crate::InputError;
+
+// Renders an error like a compiler diagnostic: the offending input line is quoted with a caret
+// under the bad span and the explanation beside it. Errors that carry no input line fall back to
+// their one-line form. Underlying sources are listed after either form.
+pub fn render(error: &InputError) -> String {
+    let line_label = error.line().map(|l| l.to_string()).unwrap_or_default();
+    let gutter = " ".repeat(line_label.len());
+
+    let mut text = match error.line_text() {
+        Some(line_text) => {
+            let column = error.column().unwrap_or(1).max(1).min(line_text.chars().count() + 1);
+            let width = error.text().map(|t| t.chars().count()).unwrap_or(0).max(1);
+
+            // Tabs are kept so the caret lines up however the terminal expands them.
+            let indent: String = line_text.chars()
+                .take(column - 1)
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+
+            [
+                format!("error: {}", error.kind()),
+                format!("{}--> {}", gutter, location(error)),
+                format!("{} |", gutter),
+                format!("{} | {}", line_label, line_text),
+                format!("{} | {}{} {}", gutter, indent, "^".repeat(width), error.message()),
+            ]
+            .join("\n")
+        },
+        None => format!("error: {}", error),
+    };
+    text.push('\n');
+
+    let mut source = std::error::Error::source(error);
+    while let Some(s) = source {
+        text.push_str(&format!("{} = caused by: {}\n", gutter, s));
+        source = s.source();
+    }
+
+    return text;
+}
+
+fn location(error: &InputError) -> String {
+    let mut location = String::from(error.file().unwrap_or("<input>"));
+    if let Some(line) = error.line() {
+        location.push_str(&format!(":{}", line));
+    }
+    if let Some(column) = error.column() {
+        location.push_str(&format!(":{}", column));
+    }
+
+    return location;
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn render_with_caret() {
+        let lines = vec!(String::from("1-3 a: abcde"), String::from("1-3 b cdefg"));
+        let error = match crate::PasswordDatabase::new(&lines) {
+            Err(e) => e.in_file("passwords.txt"),
+            Ok(_) => panic!("expected a malformed entry"),
+        };
+
+        let expected = concat!(
+            "error: malformed password entry\n",
+            " --> passwords.txt:2:5\n",
+            "  |\n",
+            "2 | 1-3 b cdefg\n",
+            "  |     ^ expected a required character followed by a colon\n",
+        );
+
+        assert_eq!(expected, super::render(&error));
+    }
+
+    #[test]
+    fn render_without_line() {
+        let error = crate::InputError::from("x".parse::<usize>().unwrap_err());
+
+        assert_eq!("error: could not parse number\n = caused by: invalid digit found in string\n", super::render(&error));
+    }
+}
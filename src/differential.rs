@@ -0,0 +1,144 @@
+use crate::challenges::DynChallenge;
+use crate::challenges::ParsedInput;
+use crate::InputError;
+use crate::PuzzleInput;
+use crate::ResultHashMap;
+
+pub struct ImplementationRun {
+    pub name: &'static str,
+    pub result: ResultHashMap,
+    pub elapsed: std::time::Duration,
+}
+
+// Every implementation of one part, run on the same parsed input.
+pub struct PartComparison {
+    pub day: usize,
+    pub part: usize,
+    pub runs: Vec<ImplementationRun>,
+}
+
+impl PartComparison {
+    // Answers agree when they have the same keys and print the same values, so a signed and an
+    // unsigned 5 agree. Failures agree when they have the same kind.
+    pub fn agrees(&self) -> bool {
+        let outcomes: Vec<Result<Vec<(String, String)>, crate::ErrorKind>> = self.runs.iter()
+            .map(|run| match &run.result {
+                Ok(answers) => {
+                    let mut printed: Vec<(String, String)> = answers.iter().map(|(k, v)| (k.clone(), v.to_string())).collect();
+                    printed.sort();
+                    Ok(printed)
+                },
+                Err(e) => Err(e.kind()),
+            })
+            .collect();
+
+        return outcomes.windows(2).all(|w| w[0] == w[1]);
+    }
+}
+
+pub fn compare_part(day: usize, challenge: &dyn DynChallenge, part: usize, input: &ParsedInput) -> PartComparison {
+    let mut runs = Vec::new();
+    for name in challenge.implementation_names(part) {
+        let start = std::time::Instant::now();
+        let result = crate::runner::catch_panics(|| challenge.solve_with(part, name, input));
+
+        runs.push(ImplementationRun { name: name, result: result, elapsed: start.elapsed() });
+    }
+
+    return PartComparison { day: day, part: part, runs: runs };
+}
+
+pub fn compare_input(day: usize, parts: &[usize], params: &[(String, String)], input: &PuzzleInput) -> Result<Vec<PartComparison>, InputError> {
+    let mut challenge = crate::registry::Registry::new().get(day)?.create();
+    challenge.set_params(params)?;
+    let parsed = crate::runner::catch_panics(|| challenge.parse_input(input))?;
+
+    return Ok(parts.iter().map(|part| compare_part(day, challenge.as_ref(), *part, &parsed)).collect());
+}
+
+// Compares on one generated input per seed. Parameters the generator supplies, such as day 9's
+// target, are applied after `params` so that they match the input.
+pub fn compare_generated(
+    day: usize,
+    parts: &[usize],
+    params: &[(String, String)],
+    seeds: std::ops::Range<u64>,
+    size: usize,
+) -> Result<Vec<(u64, Vec<PartComparison>)>, InputError> {
+    let mut comparisons = Vec::new();
+    for seed in seeds {
+        let generated = crate::generator::generate(day, seed, size)?;
+        let mut seed_params = params.to_vec();
+        seed_params.extend(generated.params.iter().cloned());
+
+        comparisons.push((seed, compare_input(day, parts, &seed_params, &PuzzleInput::from_text(&generated.text))?));
+    }
+
+    return Ok(comparisons);
+}
+
+pub fn print_comparison(out: &mut dyn std::io::Write, comparison: &PartComparison) -> std::io::Result<()> {
+    let status = if comparison.agrees() { "agree" } else { "DISAGREE" };
+    writeln!(out, "Day {} part {}: {} implementation(s) {}", comparison.day, comparison.part, comparison.runs.len(), status)?;
+
+    for run in &comparison.runs {
+        let outcome = match &run.result {
+            Ok(answers) => crate::ledger::part_answer(answers),
+            Err(e) => format!("error: {}", crate::runner::error_message(e)),
+        };
+        writeln!(out, "  {:<16} {:>12.3?}  {}", run.name, run.elapsed, outcome)?;
+    }
+
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn alternatives_agree_on_generated_inputs() {
+        for day in &[9, 10] {
+            let comparisons = super::compare_generated(*day, &[1, 2], &[], 0..20, 150).unwrap();
+
+            for (seed, parts) in &comparisons {
+                for comparison in parts {
+                    assert!(!comparison.runs.is_empty());
+                    assert!(comparison.agrees(), "day {} part {} disagrees for seed {}", day, comparison.part, seed);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn disagreements_are_reported() {
+        let run = |name, result| super::ImplementationRun { name: name, result: result, elapsed: std::time::Duration::default() };
+        let answer = |v: u64| -> crate::ResultHashMap { Ok([(String::from("total"), crate::Answer::from(v))].iter().cloned().collect()) };
+        let failure = |kind| -> crate::ResultHashMap { Err(crate::InputError::new(kind, "failed")) };
+
+        let comparison = |runs| super::PartComparison { day: 1, part: 1, runs: runs };
+        assert!(comparison(vec!(run("a", answer(5)), run("b", answer(5)))).agrees());
+        assert!(!comparison(vec!(run("a", answer(5)), run("b", answer(6)))).agrees());
+        assert!(!comparison(vec!(run("a", answer(5)), run("b", failure(crate::ErrorKind::NoSolution)))).agrees());
+        assert!(comparison(vec!(run("a", failure(crate::ErrorKind::NoSolution)), run("b", failure(crate::ErrorKind::NoSolution)))).agrees());
+    }
+
+    #[test]
+    fn runs_of_one_number_are_not_contiguous_sums() {
+        // Day 9's target may itself appear in the list; no implementation may count it as a run.
+        let input = crate::PuzzleInput::from_text("1\n2\n4\n");
+        let params = [(String::from("preamble"), String::from("1")), (String::from("target"), String::from("4"))];
+        let comparisons = super::compare_input(9, &[2], &params, &input).unwrap();
+
+        assert_eq!(vec!("default", "sliding window"), comparisons[0].runs.iter().map(|r| r.name).collect::<Vec<&str>>());
+        assert!(comparisons[0].agrees());
+        assert!(comparisons[0].runs[0].result.is_err());
+    }
+
+    #[test]
+    fn unknown_implementations_are_errors() {
+        let challenge = crate::registry::Registry::new().get(10).unwrap().create();
+        let parsed = challenge.parse_input(&crate::PuzzleInput::from_text("1\n2\n")).unwrap();
+
+        assert_eq!(crate::ErrorKind::UnknownImplementation, challenge.solve_with(2, "fastest", &parsed).unwrap_err().kind());
+        assert!(challenge.implementation_names(3).is_empty());
+    }
+}
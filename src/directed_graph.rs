@@ -1,3 +1,7 @@
+use crate::input_error::ErrorKind;
+use crate::input_error::InputError;
+
+#[derive(Clone)]
 pub struct Node {
     name: String,
 }
@@ -12,9 +16,15 @@ impl Node {
     }
 }
 
+// Edges are adjacency lists of (end index, weight), so sparse graphs with many nodes stay small.
+// Serialized edges name their nodes rather than using indices.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "GraphData", try_from = "GraphData"))]
 pub struct DirectedGraph {
     pub nodes: Vec<Node>,
-    edges: Vec<Vec<i64>>,
+    edges: Vec<Vec<(usize, i64)>>,
+    node_indices: std::collections::HashMap<String, usize>,
 }
 
 impl DirectedGraph {
@@ -22,6 +32,7 @@ impl DirectedGraph {
         return DirectedGraph {
             nodes: Vec::new(),
             edges: Vec::new(),
+            node_indices: std::collections::HashMap::new(),
         };
     }
 
@@ -30,54 +41,152 @@ impl DirectedGraph {
     }
 
     pub fn add_node_if_not_exists(&mut self, name: String) {
-        if self.nodes.iter().any(|n| n.name == name) {
+        if self.node_indices.contains_key(&name) {
             return;
         }
 
+        self.node_indices.insert(name.clone(), self.nodes.len());
         self.nodes.push(Node::new(name));
+        self.edges.push(Vec::new());
+    }
 
-        for v in &mut self.edges {
-            v.push(0);
+    // Edges between unknown nodes are ignored, and a repeated edge replaces the earlier weight.
+    pub fn add_edge_by_names(&mut self, start: &str, end: &str, weight: i64) {
+        let (start_index, end_index) = match (self.node_indices.get(start), self.node_indices.get(end)) {
+            (Some(s), Some(e)) => (*s, *e),
+            _ => return,
+        };
+
+        let edges = &mut self.edges[start_index];
+        match edges.iter_mut().find(|e| e.0 == end_index) {
+            Some(edge) => edge.1 = weight,
+            None => edges.push((end_index, weight)),
         }
-        self.edges.push(std::iter::repeat(0).take(self.nodes.len()).collect());
     }
 
-    pub fn add_edge_by_names(&mut self, start: &str, end: &str, weight: i64) {
-        let start_index = self.nodes.iter().position(|n| n.name == *start).unwrap();
-        let end_index = self.nodes.iter().position(|n| n.name == *end).unwrap();
+    // Each edge contributes its weight, plus its weight times everything below its end node.
+    // Totals are memoised and computed without recursion, so deep or cyclic graphs are reported
+    // rather than overflowing the stack.
+    pub fn sum_of_path_weight_products(&self, start: &str) -> Result<i64, InputError> {
+        let start_index = match self.node_indices.get(start) {
+            Some(i) => *i,
+            None => return Err(InputError::new(ErrorKind::NoSolution, format!("no node named {}", start)).with_text(start)),
+        };
+
+        let mut totals: Vec<Option<i64>> = vec![None; self.nodes.len()];
+        let mut on_path = vec![false; self.nodes.len()];
+        let mut stack = vec!(start_index);
+        while let Some(&current) = stack.last() {
+            if totals[current].is_some() {
+                stack.pop();
+                continue;
+            }
+            on_path[current] = true;
+
+            let mut pending = None;
+            let mut total: i64 = 0;
+            for (end, weight) in &self.edges[current] {
+                if on_path[*end] && totals[*end].is_none() {
+                    return Err(InputError::new(ErrorKind::NoSolution, format!("{} eventually contains itself", self.nodes[*end].name)));
+                }
+
+                match totals[*end] {
+                    Some(below) => {
+                        total = match weight.checked_mul(below).and_then(|t| t.checked_add(*weight)).and_then(|t| t.checked_add(total)) {
+                            Some(t) => t,
+                            None => return Err(InputError::new(ErrorKind::NoSolution, "path weight products overflow")),
+                        };
+                    },
+                    None => {
+                        pending = Some(*end);
+                        break;
+                    },
+                }
+            }
+
+            match pending {
+                Some(end) => stack.push(end),
+                None => {
+                    totals[current] = Some(total);
+                    on_path[current] = false;
+                    stack.pop();
+                },
+            }
+        }
 
-        self.edges[start_index][end_index] = weight;
+        return Ok(totals[start_index].unwrap_or(0));
     }
 
-    pub fn sum_of_path_weight_products(&self, start: &str) -> i64{
-        let current_node = (self.nodes.iter().position(|n| n.name == *start).unwrap(), 0);
-        let total_sum = 0;
+    // An unknown start node yields nothing.
+    pub fn iter_from_node(&self, start: &str) -> DepthFirstIter<'_> {
+        let stack = match self.node_indices.get(start) {
+            Some(i) => vec!((*i, 0)),
+            None => Vec::new(),
+        };
 
-        return self.sum_of_path_weight_product_traversal(current_node, total_sum);
+        return DepthFirstIter {
+            nodes: &self.nodes,
+            edges: &self.edges,
+            visited_indices: std::collections::HashSet::new(),
+            stack: stack,
+        };
     }
+}
 
-    fn sum_of_path_weight_product_traversal(&self, current_node: (usize, i64), total_sum: i64) -> i64 {
-        let new_edges: Vec<(usize, i64)> = self.edges[current_node.0].iter().enumerate()
-            .filter(|e| *e.1 > 0)
-            .map(|e| (e.0, *e.1))
-            .collect();
+impl Default for DirectedGraph {
+    fn default() -> DirectedGraph {
+        return DirectedGraph::new();
+    }
+}
 
-        if new_edges.len() == 0 {
-            return 0;
-        } else {
-            let mut new_sum = total_sum;
-            for e in new_edges {
-                new_sum += e.1 + e.1 * self.sum_of_path_weight_product_traversal(e, total_sum);
-            }
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct GraphData {
+    nodes: Vec<String>,
+    edges: Vec<EdgeData>,
+}
 
-            return new_sum;
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct EdgeData {
+    start: String,
+    end: String,
+    weight: i64,
+}
+
+#[cfg(feature = "serde")]
+impl From<DirectedGraph> for GraphData {
+    fn from(graph: DirectedGraph) -> GraphData {
+        let mut edges = Vec::new();
+        for (start, node_edges) in graph.edges.iter().enumerate() {
+            for (end, weight) in node_edges {
+                edges.push(EdgeData { start: graph.nodes[start].name.clone(), end: graph.nodes[*end].name.clone(), weight: *weight });
+            }
         }
+
+        return GraphData { nodes: graph.get_node_names(), edges: edges };
     }
+}
 
-    pub fn iter_from_node(&self, start: &str) -> DepthFirstIter {
-        let start_index = self.nodes.iter().position(|n| n.name == *start).unwrap();
+#[cfg(feature = "serde")]
+impl std::convert::TryFrom<GraphData> for DirectedGraph {
+    type Error = InputError;
 
-        return DepthFirstIter::new(&self.nodes, &self.edges, start_index);
+    fn try_from(data: GraphData) -> Result<DirectedGraph, InputError> {
+        let mut graph = DirectedGraph::new();
+        for name in data.nodes {
+            graph.add_node_if_not_exists(name);
+        }
+        for edge in data.edges {
+            for name in &[&edge.start, &edge.end] {
+                if !graph.node_indices.contains_key(*name) {
+                    return Err(InputError::new(ErrorKind::InputMismatch, "edge refers to a node that is not in the graph").with_text(name));
+                }
+            }
+            graph.add_edge_by_names(&edge.start, &edge.end, edge.weight);
+        }
+
+        return Ok(graph);
     }
 }
 
@@ -85,8 +194,11 @@ impl std::fmt::Display for DirectedGraph {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "Nodes: {}\n", self.nodes.iter().map(|n| n.name.clone()).collect::<Vec<String>>().join(", "))?;
 
-        for v in &self.edges {
-            writeln!(f, "  {}", v.iter().map(|i| i.to_string()).collect::<Vec<String>>().join(" "))?;
+        for (node, edges) in self.nodes.iter().zip(&self.edges) {
+            let edge_names: Vec<String> = edges.iter()
+                .map(|e| format!("{} ({})", self.nodes[e.0].name, e.1))
+                .collect();
+            writeln!(f, "  {} -> {}", node.name, edge_names.join(", "))?;
         }
 
         return std::fmt::Result::Ok(());
@@ -95,13 +207,13 @@ impl std::fmt::Display for DirectedGraph {
 
 pub struct DepthFirstIter<'a> {
     nodes: &'a Vec<Node>,
-    edges: &'a Vec<Vec<i64>>,
+    edges: &'a Vec<Vec<(usize, i64)>>,
     visited_indices: std::collections::HashSet<usize>,
     stack: Vec<(usize, i64)>,
 }
 
 impl<'a> DepthFirstIter<'a> {
-    pub fn new(nodes: &'a Vec<Node>, edges: &'a Vec<Vec<i64>>, start_index: usize) -> DepthFirstIter<'a> {
+    pub fn new(nodes: &'a Vec<Node>, edges: &'a Vec<Vec<(usize, i64)>>, start_index: usize) -> DepthFirstIter<'a> {
         return DepthFirstIter {
             nodes: nodes,
             edges: edges,
@@ -119,10 +231,10 @@ impl<'a> Iterator for DepthFirstIter<'a> {
             Some(weighted_e) => {
                 self.visited_indices.insert(weighted_e.0);
 
-                let new_edges: Vec<(usize, i64)> = self.edges[weighted_e.0].iter().enumerate()
-                    .filter(|e| *e.1 > 0)
+                let new_edges: Vec<(usize, i64)> = self.edges[weighted_e.0].iter()
+                    .filter(|e| e.1 > 0)
                     .filter(|e| !self.visited_indices.contains(&e.0))
-                    .map(|e| (e.0, *e.1))
+                    .cloned()
                     .collect();
                 for existing_edge in new_edges {
                     self.stack.push(existing_edge);
@@ -134,3 +246,53 @@ impl<'a> Iterator for DepthFirstIter<'a> {
         };
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::DirectedGraph;
+
+    fn graph(edges: &[(&str, &str, i64)]) -> DirectedGraph {
+        let mut graph = DirectedGraph::new();
+        for (start, end, weight) in edges {
+            graph.add_node_if_not_exists(start.to_string());
+            graph.add_node_if_not_exists(end.to_string());
+            graph.add_edge_by_names(start, end, *weight);
+        }
+
+        return graph;
+    }
+
+    #[test]
+    fn sum_of_path_weight_products_shares_subgraphs() {
+        let graph = graph(&[("a", "b", 2), ("a", "c", 1), ("b", "d", 3), ("c", "d", 1)]);
+
+        // a holds 2 b (each holding 3 d) and 1 c (holding 1 d): 2 + 2*3 + 1 + 1*1.
+        assert_eq!(10, graph.sum_of_path_weight_products("a").unwrap());
+        assert_eq!(0, graph.sum_of_path_weight_products("d").unwrap());
+    }
+
+    #[test]
+    fn sum_of_path_weight_products_rejects_cycles_and_unknown_nodes() {
+        let graph = graph(&[("a", "b", 1), ("b", "c", 1), ("c", "a", 1)]);
+
+        assert!(graph.sum_of_path_weight_products("a").is_err());
+        assert!(graph.sum_of_path_weight_products("z").is_err());
+        assert_eq!(0, graph.iter_from_node("z").count());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip_names_edges() {
+        let json = serde_json::to_string(&graph(&[("a", "b", 2), ("b", "c", 3)])).unwrap();
+        assert_eq!(
+            r#"{"nodes":["a","b","c"],"edges":[{"start":"a","end":"b","weight":2},{"start":"b","end":"c","weight":3}]}"#,
+            json
+        );
+
+        let restored: DirectedGraph = serde_json::from_str(&json).unwrap();
+        assert_eq!(8, restored.sum_of_path_weight_products("a").unwrap());
+
+        let dangling = r#"{"nodes":["a"],"edges":[{"start":"a","end":"z","weight":1}]}"#;
+        assert!(serde_json::from_str::<DirectedGraph>(dangling).is_err());
+    }
+}
@@ -1,58 +1,505 @@
+use crate::input_error::ErrorKind;
 use crate::input_error::InputError;
+use crate::input::PuzzleInput;
+use crate::Amount;
+use crate::Product;
+use std::convert::TryFrom;
 
+// Amounts are held in units of the report's scale, the most decimal places on any line, so with
+// a line of 3.25 the amounts 12 and 0.5 are held as 1200 and 50.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExpenseReport {
-    values: Vec<usize>,
+    values: Vec<i64>,
+    scale: u32,
 }
 
 impl ExpenseReport {
     pub fn new(values: &Vec<String>) -> Result<ExpenseReport, InputError> {
-        let mut parsed_values = Vec::<usize>::new();
-        for value in values {
-            parsed_values.push(
-                value
-                .parse::<usize>()
-                .map_err(InputError::Parse)?
-                );
+        let mut amounts = Vec::<Amount>::new();
+        for (i, value) in values.iter().enumerate() {
+            amounts.push(value.parse::<Amount>().map_err(|e| e.with_line_text(value).at_line(i + 1))?);
+        }
+
+        let scale = amounts.iter().map(|a| a.scale()).max().unwrap_or(0);
+        let mut scaled_values = Vec::with_capacity(amounts.len());
+        for (i, amount) in amounts.iter().enumerate() {
+            let scaled = amount.rescale(scale).ok_or_else(|| {
+                InputError::new(ErrorKind::MalformedExpense, format!("amount is too large to hold with {} decimal places", scale))
+                    .with_text(&values[i])
+                    .with_line_text(&values[i])
+                    .at_column(1)
+                    .at_line(i + 1)
+            })?;
+            scaled_values.push(scaled.units());
         }
 
         return Ok(
-            ExpenseReport { values: parsed_values },
+            ExpenseReport { values: scaled_values, scale: scale },
         );
     }
 
+    pub fn scale(&self) -> u32 {
+        return self.scale;
+    }
+
+    #[deprecated(note = "use target_two_sum or target_k_sum, which report the matched entries")]
     pub fn product_from_target_two_sum(&mut self, target_sum: usize) -> usize {
-        self.values.retain(|&v| v <= target_sum);
-        for value1 in &self.values {
-            for value2 in (&self.values).into_iter().filter(|&v| value1 + v == target_sum).collect::<Vec<&usize>>() {
-                return value1 * value2;
-            }
+        return self.product_from_target_k_sum(2, target_sum);
+    }
+
+    #[deprecated(note = "use target_three_sum or target_k_sum, which report the matched entries")]
+    pub fn product_from_target_three_sum(&mut self, target_sum: usize) -> usize {
+        return self.product_from_target_k_sum(3, target_sum);
+    }
+
+    // The old products may use a line more than once and answer 0 when nothing matches, or now
+    // when the product is not a whole number that fits a usize.
+    fn product_from_target_k_sum(&self, k: usize, target_sum: usize) -> usize {
+        let target_sum = match i64::try_from(target_sum) {
+            Ok(t) => Amount::whole(t),
+            Err(_) => return 0,
+        };
+        let product = match self.target_k_sum(k, target_sum, EntrySelection::WithReplacement) {
+            Ok(Some(found)) => found.product(),
+            _ => return 0,
+        };
+        if product.scale() > 0 {
+            return 0;
         }
 
-        return 0;
+        return usize::try_from(product.units()).unwrap_or(0);
     }
 
-    pub fn product_from_target_three_sum(&mut self, target_sum: usize) -> usize {
-        self.values.retain(|&v| v <= target_sum);
-        for value1 in &self.values {
-            for value2 in (&self.values).into_iter().filter(|&v| value1 + v <= target_sum).collect::<Vec<&usize>>() {
-                for value3 in (&self.values).into_iter().filter(|&v| value1 + value2 + v == target_sum).collect::<Vec<&usize>>() {
-                    return value1 * value2 * value3;
+    pub fn target_two_sum(&self, target_sum: Amount) -> Result<Option<ExpenseMatch>, InputError> {
+        return self.target_k_sum(2, target_sum, EntrySelection::Distinct);
+    }
+
+    pub fn target_three_sum(&self, target_sum: Amount) -> Result<Option<ExpenseMatch>, InputError> {
+        return self.target_k_sum(3, target_sum, EntrySelection::Distinct);
+    }
+
+    // Finds k entries that sum to the target, or None when no k entries do. Pairs are found with a hash map in one pass; larger
+    // k sorts once and narrows down to a two-pointer scan.
+    pub fn target_k_sum(&self, k: usize, target_sum: Amount, selection: EntrySelection) -> Result<Option<ExpenseMatch>, InputError> {
+        // A target with more decimal places than any entry cannot be reached.
+        let target = match target_sum.units_at_scale(self.scale) {
+            Some(target) => target,
+            None => return Ok(None),
+        };
+        let mut candidates = self.candidates(k, target);
+
+        let entries = match k {
+            _ if k > available(candidates.len(), selection) => None,
+            0 => if target == 0 { Some(Vec::new()) } else { None },
+            1 => candidates.iter().find(|e| units(e) == target).map(|e| vec!(*e)),
+            2 => two_sum_by_hashing(&candidates, target, selection),
+            _ => {
+                candidates.sort_by_key(|e| e.amount.units());
+                k_sum_of_sorted(&candidates, k, target, selection, |first, _, remaining| {
+                    two_sum_of_sorted(&candidates[first..], remaining, selection)
+                })
+            },
+        };
+
+        return Ok(entries.map(|entries| ExpenseMatch::new(entries, report_sum(target_sum, self.scale))));
+    }
+
+    // Every way of choosing k lines that sum to the target, in ascending order of their amounts.
+    // Lines holding equal amounts give separate solutions.
+    pub fn target_k_sums(&self, k: usize, target_sum: Amount, selection: EntrySelection) -> KSumSolutions<'static> {
+        let mut sorted = match target_sum.units_at_scale(self.scale) {
+            Some(target) => self.candidates(k, target),
+            None => Vec::new(),
+        };
+        sorted.sort_by_key(|e| e.amount.units());
+
+        return KSumSolutions::new(std::borrow::Cow::Owned(sorted), k, target_sum, self.scale, selection);
+    }
+
+    // For many queries against the same report, build the index once and query it instead.
+    pub fn index(&self) -> ExpenseIndex {
+        return ExpenseIndex::new(self);
+    }
+
+    fn entries(&self) -> impl Iterator<Item = ExpenseEntry> + '_ {
+        return self.values.iter().enumerate().map(move |(i, &v)| ExpenseEntry { line: i + 1, amount: Amount::new(v, self.scale) });
+    }
+
+    fn candidates(&self, k: usize, target: i128) -> Vec<ExpenseEntry> {
+        let largest = largest_usable(k, target, self.values.iter().min().cloned());
+
+        return self.entries().filter(|e| units(e) <= largest).collect();
+    }
+}
+
+fn units(entry: &ExpenseEntry) -> i128 {
+    return entry.amount.units() as i128;
+}
+
+// Refunds let an entry above the target take part, but only by as much as the other k - 1
+// entries can bring the sum back down.
+fn largest_usable(k: usize, target: i128, smallest: Option<i64>) -> i128 {
+    return match smallest {
+        Some(smallest) => target.saturating_sub((k.saturating_sub(1) as i128).saturating_mul(smallest as i128)),
+        None => target,
+    };
+}
+
+fn available(candidates: usize, selection: EntrySelection) -> usize {
+    return match selection {
+        EntrySelection::Distinct => candidates,
+        EntrySelection::WithReplacement => if candidates == 0 { 0 } else { usize::MAX },
+    };
+}
+
+// Sums are given at the report's scale, like the entries, unless they are too large to hold there.
+fn report_sum(target_sum: Amount, scale: u32) -> Amount {
+    return target_sum.rescale(scale).unwrap_or(target_sum);
+}
+
+// Whether a line may be chosen more than once in the same sum.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntrySelection {
+    Distinct,
+    WithReplacement,
+}
+
+// An amount is at the scale of the report it came from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExpenseEntry {
+    pub line: usize,
+    pub amount: Amount,
+}
+
+// The entries chosen for one sum, in line order, together with the target they sum to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExpenseMatch {
+    entries: Vec<ExpenseEntry>,
+    sum: Amount,
+}
+
+impl ExpenseMatch {
+    fn new(mut entries: Vec<ExpenseEntry>, sum: Amount) -> ExpenseMatch {
+        entries.sort_by_key(|e| e.line);
+        return ExpenseMatch { entries: entries, sum: sum };
+    }
+
+    pub fn entries(&self) -> &[ExpenseEntry] {
+        return &self.entries;
+    }
+
+    pub fn values(&self) -> Vec<Amount> {
+        return self.entries.iter().map(|e| e.amount).collect();
+    }
+
+    pub fn lines(&self) -> Vec<usize> {
+        return self.entries.iter().map(|e| e.line).collect();
+    }
+
+    pub fn sum(&self) -> Amount {
+        return self.sum;
+    }
+
+    pub fn product(&self) -> Product {
+        return Product::of(&self.values());
+    }
+}
+
+// A depth-first search over the entries sorted by amount. `chosen` holds positions into `sorted`
+// and `next` is the next position to try for the following slot.
+pub struct KSumSolutions<'a> {
+    sorted: std::borrow::Cow<'a, [ExpenseEntry]>,
+    k: usize,
+    target_sum: Amount,
+    selection: EntrySelection,
+    chosen: Vec<usize>,
+    remaining: i128,
+    next: usize,
+    done: bool,
+}
+
+impl<'a> KSumSolutions<'a> {
+    fn new(sorted: std::borrow::Cow<'a, [ExpenseEntry]>, k: usize, target_sum: Amount, scale: u32, selection: EntrySelection) -> KSumSolutions<'a> {
+        let target = target_sum.units_at_scale(scale);
+
+        return KSumSolutions {
+            sorted: sorted,
+            k: k,
+            target_sum: report_sum(target_sum, scale),
+            selection: selection,
+            chosen: Vec::new(),
+            remaining: target.unwrap_or(0),
+            next: 0,
+            done: target.is_none(),
+        };
+    }
+
+    fn backtrack(&mut self) {
+        match self.chosen.pop() {
+            Some(position) => {
+                self.remaining += units(&self.sorted[position]);
+                self.next = position + 1;
+            },
+            None => self.done = true,
+        }
+    }
+
+    fn found(&self, last: usize) -> ExpenseMatch {
+        let mut entries: Vec<ExpenseEntry> = self.chosen.iter().map(|&p| self.sorted[p]).collect();
+        entries.push(self.sorted[last]);
+        return ExpenseMatch::new(entries, self.target_sum);
+    }
+}
+
+impl Iterator for KSumSolutions<'_> {
+    type Item = ExpenseMatch;
+
+    fn next(&mut self) -> Option<ExpenseMatch> {
+        if self.k == 0 && !self.done {
+            self.done = true;
+            return if self.remaining == 0 { Some(ExpenseMatch::new(Vec::new(), self.target_sum)) } else { None };
+        }
+
+        while !self.done {
+            let slots = self.k - self.chosen.len();
+            let amount = match self.sorted.get(self.next) {
+                Some(entry) => units(entry),
+                None => {
+                    self.backtrack();
+                    continue;
+                },
+            };
+            // Amounts only grow from here, so the rest of this slot's candidates cannot fit either.
+            if amount.checked_mul(slots as i128).map_or(amount > 0, |smallest| smallest > self.remaining) {
+                self.backtrack();
+                continue;
+            }
+
+            if slots == 1 {
+                if amount == self.remaining {
+                    let found = self.found(self.next);
+                    self.next += 1;
+                    return Some(found);
                 }
+                let remaining = self.remaining;
+                self.next += self.sorted[self.next..].partition_point(|e| units(e) < remaining);
+                continue;
+            }
+
+            self.chosen.push(self.next);
+            self.remaining -= amount;
+            if self.selection == EntrySelection::Distinct {
+                self.next += 1;
+            }
+        }
+
+        return None;
+    }
+}
+
+// The report's entries sorted by amount, together with each amount's lines. The map is built
+// once, so pair queries look complements up in it rather than scanning the entries.
+#[derive(Clone, Debug)]
+pub struct ExpenseIndex {
+    sorted: Vec<ExpenseEntry>,
+    lines_by_amount: std::collections::HashMap<i64, Vec<usize>>,
+    scale: u32,
+}
+
+impl ExpenseIndex {
+    pub fn new(expense_report: &ExpenseReport) -> ExpenseIndex {
+        let mut sorted: Vec<ExpenseEntry> = expense_report.entries().collect();
+        sorted.sort_by_key(|e| e.amount.units());
+
+        let mut lines_by_amount = std::collections::HashMap::<i64, Vec<usize>>::new();
+        for entry in &sorted {
+            lines_by_amount.entry(entry.amount.units()).or_default().push(entry.line);
+        }
+
+        return ExpenseIndex {
+            sorted: sorted,
+            lines_by_amount: lines_by_amount,
+            scale: expense_report.scale,
+        };
+    }
+
+    pub fn len(&self) -> usize {
+        return self.sorted.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.sorted.is_empty();
+    }
+
+    pub fn target_k_sum(&self, k: usize, target_sum: Amount, selection: EntrySelection) -> Result<Option<ExpenseMatch>, InputError> {
+        let target = match target_sum.units_at_scale(self.scale) {
+            Some(target) => target,
+            None => return Ok(None),
+        };
+
+        let entries = match k {
+            _ if k > available(self.sorted.len(), selection) => None,
+            0 => if target == 0 { Some(Vec::new()) } else { None },
+            1 => i64::try_from(target).ok()
+                .and_then(|t| self.lines_by_amount.get(&t))
+                .map(|lines| vec!(ExpenseEntry { line: lines[0], amount: Amount::new(target as i64, self.scale) })),
+            _ => k_sum_of_sorted(&self.sorted, k, target, selection, |first, chosen, remaining| {
+                self.pair(first, chosen, remaining, selection)
+            }),
+        };
+
+        return Ok(entries.map(|entries| ExpenseMatch::new(entries, report_sum(target_sum, self.scale))));
+    }
+
+    pub fn target_k_sums(&self, k: usize, target_sum: Amount, selection: EntrySelection) -> KSumSolutions<'_> {
+        let candidates = match target_sum.units_at_scale(self.scale) {
+            Some(target) => self.candidates(k, target),
+            None => &[],
+        };
+
+        return KSumSolutions::new(std::borrow::Cow::Borrowed(candidates), k, target_sum, self.scale, selection);
+    }
+
+    fn candidates(&self, k: usize, target: i128) -> &[ExpenseEntry] {
+        let largest = largest_usable(k, target, self.sorted.first().map(|e| e.amount.units()));
+
+        return &self.sorted[..self.sorted.partition_point(|e| units(e) <= largest)];
+    }
+
+    // The first pair from position `first` on that makes up `remaining`, with the second entry's
+    // amount looked up in the map rather than scanned for.
+    fn pair(&self, first: usize, chosen: &[usize], remaining: i128, selection: EntrySelection) -> Option<Vec<ExpenseEntry>> {
+        for (position, entry) in self.sorted.iter().enumerate().skip(first) {
+            if position > first && entry.amount == self.sorted[position - 1].amount {
+                continue;
+            }
+            let complement = remaining - units(entry);
+            if complement < units(entry) {
+                return None;
+            }
+            let lines = match i64::try_from(complement).ok().and_then(|c| self.lines_by_amount.get(&c)) {
+                Some(lines) => lines,
+                None => continue,
+            };
+
+            let line = match selection {
+                EntrySelection::Distinct => lines.iter().find(|&&l| l != entry.line && chosen.iter().all(|&p| self.sorted[p].line != l)),
+                EntrySelection::WithReplacement => lines.first(),
+            };
+            if let Some(&line) = line {
+                return Some(vec!(*entry, ExpenseEntry { line: line, amount: Amount::new(complement as i64, self.scale) }));
+            }
+        }
+
+        return None;
+    }
+}
+
+fn two_sum_by_hashing(entries: &[ExpenseEntry], target: i128, selection: EntrySelection) -> Option<Vec<ExpenseEntry>> {
+    let mut seen = std::collections::HashMap::new();
+    for entry in entries {
+        if selection == EntrySelection::WithReplacement {
+            seen.entry(entry.amount.units()).or_insert(*entry);
+        }
+        let complement = i64::try_from(target - units(entry)).ok().and_then(|c| seen.get(&c));
+        if let Some(complement) = complement {
+            return Some(vec!(*complement, *entry));
+        }
+        seen.entry(entry.amount.units()).or_insert(*entry);
+    }
 
+    return None;
+}
+
+// Chooses all but the last two entries depth first, on an explicit stack so that a large k cannot
+// overflow the call stack, and hands the last two to `pair` with the position to start from, the
+// positions chosen so far and the amount still needed. Sums are taken in i128, which no k amounts
+// of i64 can overflow.
+fn k_sum_of_sorted<F>(sorted: &[ExpenseEntry], k: usize, target: i128, selection: EntrySelection, pair: F) -> Option<Vec<ExpenseEntry>>
+where
+    F: Fn(usize, &[usize], i128) -> Option<Vec<ExpenseEntry>>,
+{
+    let mut chosen: Vec<usize> = Vec::new();
+    let mut remaining = target;
+    let mut next = 0;
+    loop {
+        let first = match (chosen.last(), selection) {
+            (Some(&position), EntrySelection::Distinct) => position + 1,
+            (Some(&position), EntrySelection::WithReplacement) => position,
+            (None, _) => 0,
+        };
+        let slots = k - chosen.len();
+
+        if slots == 2 {
+            if let Some(mut entries) = pair(first, &chosen, remaining) {
+                entries.extend(chosen.iter().map(|&p| sorted[p]));
+                return Some(entries);
+            }
+        } else if next < sorted.len() {
+            // An amount equal to the one before it would only repeat that search.
+            if next > first && sorted[next].amount == sorted[next - 1].amount {
+                next += 1;
+                continue;
             }
+            // The smallest remaining amounts already exceed the target, so no later start can work.
+            let amount = units(&sorted[next]);
+            if amount.checked_mul(slots as i128).map_or(amount <= 0, |smallest| smallest <= remaining) {
+                chosen.push(next);
+                remaining -= amount;
+                if selection == EntrySelection::Distinct {
+                    next += 1;
+                }
+                continue;
+            }
+        }
+
+        match chosen.pop() {
+            Some(position) => {
+                remaining += units(&sorted[position]);
+                next = position + 1;
+            },
+            None => return None,
+        }
+    }
+}
+
+fn two_sum_of_sorted(sorted: &[ExpenseEntry], target: i128, selection: EntrySelection) -> Option<Vec<ExpenseEntry>> {
+    // `end` is one past the high pointer, so it cannot underflow when the two pointers meet.
+    let (mut low, mut end) = (0, sorted.len());
+    while low + 1 < end || (low + 1 == end && selection == EntrySelection::WithReplacement) {
+        match (units(&sorted[low]) + units(&sorted[end - 1])).cmp(&target) {
+            std::cmp::Ordering::Equal => return Some(vec!(sorted[low], sorted[end - 1])),
+            std::cmp::Ordering::Less => low += 1,
+            std::cmp::Ordering::Greater => end -= 1,
         }
+    }
+
+    return None;
+}
 
-        return 0;
+impl std::convert::TryFrom<&str> for ExpenseReport {
+    type Error = InputError;
+
+    fn try_from(text: &str) -> Result<ExpenseReport, InputError> {
+        return ExpenseReport::new(&PuzzleInput::from_text(text).content_lines().to_vec());
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::InputError;
+    use super::Amount;
+    use super::ExpenseEntry;
+    use super::ExpenseMatch;
     use super::ExpenseReport;
+    use super::EntrySelection;
+    use std::convert::TryFrom;
 
     #[test]
-    fn product_from_target_two_sum() -> Result<(), InputError> {
+    fn target_two_sum() -> Result<(), InputError> {
         let values = vec!(
             String::from("1721"),
             String::from("979"),
@@ -61,18 +508,21 @@ mod tests {
             String::from("675"),
             String::from("1456"),
         );
-        let mut expense_report = ExpenseReport::new(&values)?;
+        let expense_report = ExpenseReport::new(&values)?;
 
-        let target_sum = 2020;
-        let actual = expense_report.product_from_target_two_sum(target_sum);
+        let target_sum = Amount::whole(2020);
+        let actual = expense_report.target_two_sum(target_sum)?.unwrap();
 
-        assert_eq!(514579, actual);
+        assert_eq!("514579", actual.product().to_string());
+        assert_eq!(whole(&[1721, 299]), actual.values());
+        assert_eq!(vec!(1, 4), actual.lines());
+        assert_eq!(target_sum, actual.sum());
 
         return Ok(());
     }
 
     #[test]
-    fn product_from_target_three_sum() -> Result<(), InputError> {
+    fn target_three_sum() -> Result<(), InputError> {
         let values = vec!(
             String::from("1721"),
             String::from("979"),
@@ -81,13 +531,278 @@ mod tests {
             String::from("675"),
             String::from("1456"),
         );
-        let mut expense_report = ExpenseReport::new(&values)?;
+        let expense_report = ExpenseReport::new(&values)?;
 
-        let target_sum = 2020;
-        let actual = expense_report.product_from_target_three_sum(target_sum);
+        let target_sum = Amount::whole(2020);
+        let actual = expense_report.target_three_sum(target_sum)?.unwrap();
 
-        assert_eq!(241861950, actual);
+        assert_eq!("241861950", actual.product().to_string());
+        assert_eq!(vec!(2, 3, 5), actual.lines());
 
         return Ok(());
     }
+
+    #[test]
+    fn try_from_text() {
+        let expense_report = ExpenseReport::try_from("1721\r\n979\r\n366\r\n299\r\n675\r\n1456").unwrap();
+
+        assert_eq!("514579", expense_report.target_two_sum(Amount::whole(2020)).unwrap().unwrap().product().to_string());
+        assert_eq!(Some(2), ExpenseReport::try_from("1721\n97x\n").err().and_then(|e| e.line()));
+    }
+
+    fn whole(values: &[i64]) -> Vec<Amount> {
+        return values.iter().map(|v| Amount::whole(*v)).collect();
+    }
+
+    fn values(found: Result<Option<ExpenseMatch>, InputError>) -> Option<Vec<Amount>> {
+        return found.unwrap().map(|m| m.values());
+    }
+
+    fn report(values: &[usize]) -> ExpenseReport {
+        return ExpenseReport::new(&values.iter().map(|v| v.to_string()).collect()).unwrap();
+    }
+
+    #[test]
+    fn target_k_sum() {
+        let expense_report = ExpenseReport::try_from("1721\n979\n366\n299\n675\n1456").unwrap();
+        let find = |k, target| values(expense_report.target_k_sum(k, Amount::whole(target), EntrySelection::Distinct));
+
+        assert_eq!(Some(whole(&[979, 366, 299, 675])), find(4, 2319));
+        assert_eq!(Some(whole(&[979])), find(1, 979));
+        assert_eq!(Some(Vec::new()), find(0, 0));
+        assert_eq!(None, find(7, 5496));
+    }
+
+    #[test]
+    fn no_solution_is_not_an_error() {
+        let expense_report = ExpenseReport::try_from("1721\n979\n366").unwrap();
+
+        for k in 0..=4 {
+            assert_eq!(None, expense_report.target_k_sum(k, Amount::whole(10), EntrySelection::Distinct).unwrap());
+            assert_eq!(None, expense_report.index().target_k_sum(k, Amount::whole(10), EntrySelection::Distinct).unwrap());
+        }
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn deprecated_products_still_answer() {
+        let mut expense_report = ExpenseReport::try_from("1721\n979\n366\n299\n675\n1456").unwrap();
+
+        assert_eq!(514579, expense_report.product_from_target_two_sum(2020));
+        assert_eq!(241861950, expense_report.product_from_target_three_sum(2020));
+        assert_eq!(0, expense_report.product_from_target_two_sum(5));
+        assert_eq!(1020100, ExpenseReport::try_from("1010").unwrap().product_from_target_two_sum(2020));
+    }
+
+    #[test]
+    fn lines_are_used_once_unless_replacement_is_allowed() {
+        let expense_report = ExpenseReport::try_from("1010\n5\n1005").unwrap();
+        let find = |k, target, selection| values(expense_report.target_k_sum(k, Amount::whole(target), selection));
+        assert!(expense_report.target_two_sum(Amount::whole(2020)).unwrap().is_none());
+        assert!(expense_report.target_three_sum(Amount::whole(3030)).unwrap().is_none());
+
+        assert_eq!(
+            &[ExpenseEntry { line: 1, amount: Amount::whole(1010) }, ExpenseEntry { line: 1, amount: Amount::whole(1010) }],
+            expense_report.target_k_sum(2, Amount::whole(2020), EntrySelection::WithReplacement).unwrap().unwrap().entries()
+        );
+        assert_eq!(Some(whole(&[1010, 1010, 1010])), find(3, 3030, EntrySelection::WithReplacement));
+        assert_eq!(Some(whole(&[5, 5, 5, 5])), find(4, 20, EntrySelection::WithReplacement));
+
+        let expense_report = ExpenseReport::try_from("1010\n5\n1010").unwrap();
+        assert_eq!(vec!(1, 3), expense_report.target_two_sum(Amount::whole(2020)).unwrap().unwrap().lines());
+        assert_eq!(Some(whole(&[1010, 5, 1010])), values(expense_report.target_three_sum(Amount::whole(2025))));
+    }
+
+    #[test]
+    fn every_solution_is_enumerated() {
+        let expense_report = ExpenseReport::try_from("1\n4\n2\n3\n2").unwrap();
+        let lines = |k, target, selection| {
+            expense_report.target_k_sums(k, Amount::whole(target), selection).map(|m| m.lines()).collect::<Vec<Vec<usize>>>()
+        };
+
+        assert_eq!(vec!(vec!(1, 2), vec!(3, 4), vec!(4, 5)), lines(2, 5, EntrySelection::Distinct));
+        assert_eq!(vec!(vec!(1, 3, 5)), lines(3, 5, EntrySelection::Distinct));
+        assert_eq!(vec!(vec!(1, 2), vec!(3, 4), vec!(4, 5)), lines(2, 5, EntrySelection::WithReplacement));
+        assert_eq!(4, lines(3, 5, EntrySelection::WithReplacement).len());
+        assert_eq!(vec!(Vec::<usize>::new()), lines(0, 0, EntrySelection::Distinct));
+        assert_eq!(0, lines(6, 5, EntrySelection::Distinct).len());
+        assert!(expense_report.target_k_sums(3, Amount::whole(5), EntrySelection::Distinct).all(|m| m.sum() == Amount::whole(5)));
+    }
+
+    #[test]
+    fn enumeration_agrees_with_the_first_solution() {
+        let mut rng = crate::generator::SeededRng::new(11);
+        for _ in 0..20 {
+            let expense_report = report(&crate::generator::expense_values(&mut rng, 40, 200, &[2, 3]).unwrap());
+
+            for k in 1..=4 {
+                for selection in &[EntrySelection::Distinct, EntrySelection::WithReplacement] {
+                    let all: Vec<ExpenseMatch> = expense_report.target_k_sums(k, Amount::whole(200), *selection).collect();
+                    let first = expense_report.target_k_sum(k, Amount::whole(200), *selection).unwrap();
+
+                    assert_eq!(all.is_empty(), first.is_none());
+                    if let Some(first) = first {
+                        assert!(all.contains(&first));
+                    }
+                    assert!(all.iter().all(|m| m.entries().len() == k && m.values().iter().map(|v| v.units()).sum::<i64>() == 200));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pair_search_stops_when_the_pointers_meet() {
+        let too_large = [ExpenseEntry { line: 1, amount: Amount::whole(1500) }];
+
+        assert_eq!(None, super::two_sum_of_sorted(&too_large, 2020, EntrySelection::WithReplacement));
+        assert_eq!(None, super::two_sum_of_sorted(&too_large, 2020, EntrySelection::Distinct));
+        assert_eq!(None, super::two_sum_of_sorted(&[], 2020, EntrySelection::WithReplacement));
+
+        let index = ExpenseReport::try_from("1500").unwrap().index();
+        assert!(index.target_k_sum(2, Amount::whole(2020), EntrySelection::WithReplacement).unwrap().is_none());
+        assert!(index.target_k_sum(2, Amount::whole(2020), EntrySelection::Distinct).unwrap().is_none());
+    }
+
+    #[test]
+    fn large_reports_are_searched_quickly() {
+        let mut rng = crate::generator::SeededRng::new(7);
+        let expense_report = report(&crate::generator::expense_values(&mut rng, 200000, 2020, &[2, 3]).unwrap());
+
+        for k in 2..=4 {
+            let found = expense_report.target_k_sum(k, Amount::whole(2020), EntrySelection::Distinct).unwrap().unwrap();
+            assert_eq!(k, found.entries().len());
+            assert_eq!(2020, found.values().iter().map(|v| v.units()).sum::<i64>());
+            assert!(found.lines().windows(2).all(|w| w[0] < w[1]));
+        }
+    }
+
+    #[test]
+    fn index_answers_queries_without_changing_the_report() {
+        let expense_report = ExpenseReport::try_from("1721\n979\n366\n299\n675\n1456\n1010").unwrap();
+        let index = expense_report.index();
+        let find = |k, target| index.target_k_sum(k, Amount::whole(target), EntrySelection::Distinct).unwrap();
+
+        assert_eq!(7, index.len());
+        assert_eq!(vec!(1, 4), find(2, 2020).unwrap().lines());
+        assert_eq!("241861950", find(3, 2020).unwrap().product().to_string());
+        assert_eq!(vec!(7), find(1, 1010).unwrap().lines());
+        assert!(find(2, 3).is_none());
+        let all = index.target_k_sums(2, Amount::whole(2020), EntrySelection::Distinct).map(|m| m.lines()).collect::<Vec<Vec<usize>>>();
+        assert_eq!(vec!(vec!(1, 4)), all);
+
+        assert_eq!(vec!(1, 4), expense_report.target_two_sum(Amount::whole(2020)).unwrap().unwrap().lines());
+
+        let index = ExpenseReport::try_from("1010\n5\n1010").unwrap().index();
+        assert_eq!(vec!(1, 3), index.target_k_sum(2, Amount::whole(2020), EntrySelection::Distinct).unwrap().unwrap().lines());
+        assert_eq!(vec!(2, 2), index.target_k_sum(2, Amount::whole(10), EntrySelection::WithReplacement).unwrap().unwrap().lines());
+        assert!(index.target_k_sum(2, Amount::whole(10), EntrySelection::Distinct).unwrap().is_none());
+    }
+
+    #[test]
+    fn index_agrees_with_the_report() {
+        let mut rng = crate::generator::SeededRng::new(5);
+        for round in 0..10 {
+            // Every other round shifts the amounts down so that some of them are refunds.
+            let shift = if round % 2 == 0 { 0 } else { 100 };
+            let amounts: Vec<String> = crate::generator::expense_values(&mut rng, 60, 300, &[2, 3]).unwrap()
+                .iter()
+                .map(|v| (*v as i64 - shift).to_string())
+                .collect();
+            let expense_report = ExpenseReport::new(&amounts).unwrap();
+            let index = expense_report.index();
+
+            for target in (-300..=600).step_by(3) {
+                for k in 0..=3 {
+                    for selection in &[EntrySelection::Distinct, EntrySelection::WithReplacement] {
+                        let from_index = index.target_k_sum(k, Amount::whole(target), *selection).unwrap();
+                        let from_report = expense_report.target_k_sum(k, Amount::whole(target), *selection).unwrap();
+                        assert_eq!(from_report.is_some(), from_index.is_some(), "k {} target {}", k, target);
+                        if let Some(found) = from_index {
+                            assert_eq!(k, found.entries().len());
+                            assert_eq!(target, found.values().iter().map(|v| v.units()).sum::<i64>());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn index_serves_many_targets() {
+        let mut rng = crate::generator::SeededRng::new(3);
+        let index = report(&crate::generator::expense_values(&mut rng, 20000, 1000000, &[2]).unwrap()).index();
+
+        let found = (0..1000).map(|_| rng.range(1, 2000000) as i64)
+            .filter_map(|target| index.target_k_sum(2, Amount::whole(target), EntrySelection::Distinct).unwrap().map(|m| (target, m)))
+            .collect::<Vec<(i64, ExpenseMatch)>>();
+
+        assert!(!found.is_empty());
+        assert!(found.iter().all(|(target, m)| m.values()[0].units() + m.values()[1].units() == *target && m.lines()[0] < m.lines()[1]));
+    }
+
+    #[test]
+    fn index_answers_repeated_pair_queries_on_a_large_report() {
+        let lines: Vec<String> = (1..=100000).map(|v| (v * 3).to_string()).collect();
+        let index = ExpenseReport::new(&lines).unwrap().index();
+
+        for target in 1..=3000 {
+            let found = index.target_k_sum(2, Amount::whole(target), EntrySelection::Distinct).unwrap();
+            if target % 3 == 0 && target >= 9 {
+                assert_eq!(vec!(1, target as usize / 3 - 1), found.unwrap().lines());
+            } else {
+                assert!(found.is_none(), "target {}", target);
+            }
+        }
+    }
+
+    #[test]
+    fn large_k_does_not_recurse() {
+        let index = ExpenseReport::try_from("1\n2").unwrap().index();
+        let found = index.target_k_sum(100000, Amount::whole(100001), EntrySelection::WithReplacement).unwrap().unwrap();
+
+        assert_eq!(100000, found.entries().len());
+        assert_eq!(vec!(1; 99999), found.lines()[..99999].to_vec());
+    }
+
+    #[test]
+    fn refunds_take_part_in_sums() {
+        let expense_report = ExpenseReport::try_from("2500\n-480\n1200\n-15\n300").unwrap();
+        let lines = |k, target| expense_report.target_k_sum(k, Amount::whole(target), EntrySelection::Distinct).unwrap().map(|m| m.lines());
+
+        assert_eq!(Some(vec!(1, 2)), lines(2, 2020));
+        assert_eq!(Some(vec!(2, 3, 5)), lines(3, 1020));
+        assert_eq!(Some(vec!(2, 4)), lines(2, -495));
+        assert_eq!("-1200000", expense_report.target_k_sum(2, Amount::whole(2020), EntrySelection::Distinct).unwrap().unwrap().product().to_string());
+        assert_eq!(vec!(vec!(1, 2)), expense_report.index().target_k_sums(2, Amount::whole(2020), EntrySelection::Distinct).map(|m| m.lines()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn decimal_amounts_share_the_report_scale() {
+        let expense_report = ExpenseReport::try_from("12.34\n7\n-0.5\n0.16").unwrap();
+        assert_eq!(2, expense_report.scale());
+
+        let found = expense_report.target_k_sum(2, "12.5".parse().unwrap(), EntrySelection::Distinct).unwrap().unwrap();
+        assert_eq!(vec!(1, 4), found.lines());
+        assert_eq!(vec!(Amount::new(1234, 2), Amount::new(16, 2)), found.values());
+        assert_eq!("1.9744", found.product().to_string());
+        assert_eq!("12.50", found.sum().to_string());
+
+        let found = expense_report.target_three_sum("12".parse().unwrap()).unwrap().unwrap();
+        assert_eq!(vec!(1, 3, 4), found.lines());
+        assert_eq!((Amount::whole(12), 2), (found.sum(), found.sum().scale()));
+        assert_eq!(vec!("12.00"), expense_report.index().target_k_sums(3, Amount::whole(12), EntrySelection::Distinct).map(|m| m.sum().to_string()).collect::<Vec<String>>());
+        assert!(expense_report.target_two_sum("12.505".parse().unwrap()).unwrap().is_none());
+        assert_eq!(Some(3), ExpenseReport::try_from("1.5\n2\n3.x").err().and_then(|e| e.line()));
+    }
+
+    #[test]
+    fn products_do_not_overflow() {
+        let big = i64::MAX / 4;
+        let expense_report = ExpenseReport::try_from(format!("{}\n{}\n{}", big, big, -big).as_str()).unwrap();
+
+        let found = expense_report.target_three_sum(Amount::whole(big)).unwrap().unwrap();
+        assert!(found.product().is_big());
+        assert_eq!(num_bigint::BigInt::from(big) * big * -big, found.product().units());
+        assert!(ExpenseReport::try_from(format!("{}\n0.5", i64::MAX).as_str()).is_err());
+    }
 }
@@ -0,0 +1,525 @@
+use crate::ErrorKind;
+use crate::InputError;
+
+// Generated puzzle text, plus any challenge parameters needed to solve it; day 9's part 2
+// target, for example, depends on the numbers generated.
+pub struct GeneratedInput {
+    pub text: String,
+    pub params: Vec<(String, String)>,
+}
+
+impl GeneratedInput {
+    fn from_lines(lines: Vec<String>) -> GeneratedInput {
+        return GeneratedInput { text: lines.join("\n") + "\n", params: Vec::new() };
+    }
+}
+
+// SplitMix64: small, fast and reproducible across platforms, which is all generated inputs need.
+pub struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> SeededRng {
+        return SeededRng { state: seed };
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+
+        return z ^ (z >> 31);
+    }
+
+    // A value in `low..=high`.
+    pub fn range(&mut self, low: usize, high: usize) -> usize {
+        return low + (self.next_u64() % (high - low + 1) as u64) as usize;
+    }
+
+    pub fn chance(&mut self, percent: usize) -> bool {
+        return self.range(1, 100) <= percent;
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        return &items[self.range(0, items.len() - 1)];
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range(0, i));
+        }
+    }
+}
+
+// Typical size of each day's real puzzle input, in that day's records.
+pub fn puzzle_size(day: usize) -> Result<usize, InputError> {
+    return match day {
+        1 => Ok(200),
+        2 => Ok(1000),
+        3 => Ok(323),
+        4 => Ok(290),
+        5 => Ok(800),
+        6 => Ok(490),
+        7 => Ok(594),
+        8 => Ok(630),
+        9 => Ok(1000),
+        10 => Ok(100),
+        _ => Err(crate::registry::unknown_day(&day.to_string())),
+    };
+}
+
+// Size counts each day's records: expense values, password lines, map rows, passports, boarding
+// passes, customs groups, bag colours, instructions, XMAS numbers or adapters.
+pub fn generate(day: usize, seed: u64, size: usize) -> Result<GeneratedInput, InputError> {
+    let mut rng = SeededRng::new(seed);
+
+    return match day {
+        1 => expense_report(&mut rng, size),
+        2 => password_database(&mut rng, size),
+        3 => slope_map(&mut rng, size),
+        4 => passports(&mut rng, size),
+        5 => boarding_passes(&mut rng, size),
+        6 => customs_groups(&mut rng, size),
+        7 => bag_rules(&mut rng, size),
+        8 => boot_code(&mut rng, size),
+        9 => xmas_values(&mut rng, size),
+        10 => joltage_adapters(&mut rng, size),
+        _ => Err(crate::registry::unknown_day(&day.to_string())),
+    };
+}
+
+fn too_small(size: usize, minimum: usize) -> Result<(), InputError> {
+    if size < minimum {
+        return Err(InputError::new(ErrorKind::InvalidParameter, format!("size must be at least {}", minimum)).with_text(&size.to_string()));
+    }
+
+    return Ok(());
+}
+
+// Values between 1 and `target`, containing k entries that sum to `target` for each k in `ks`.
+// The remaining values are random and may form further solutions.
+pub fn expense_values(rng: &mut SeededRng, size: usize, target: usize, ks: &[usize]) -> Result<Vec<usize>, InputError> {
+    too_small(size, ks.iter().sum())?;
+    for k in ks {
+        if *k == 0 || *k > target || target < 2 {
+            return Err(InputError::new(ErrorKind::InvalidParameter, format!("{} positive values cannot sum to {}", k, target)));
+        }
+    }
+
+    let mut values = Vec::with_capacity(size);
+    for k in ks {
+        // Distinct cut points in 1..target split it into k positive parts.
+        let mut cuts: Vec<usize> = Vec::new();
+        while cuts.len() < k - 1 {
+            let cut = rng.range(1, target - 1);
+            if !cuts.contains(&cut) {
+                cuts.push(cut);
+            }
+        }
+        cuts.sort();
+        cuts.push(target);
+
+        let mut previous = 0;
+        for cut in cuts {
+            values.push(cut - previous);
+            previous = cut;
+        }
+    }
+    while values.len() < size {
+        values.push(rng.range(1, target - 1));
+    }
+    rng.shuffle(&mut values);
+
+    return Ok(values);
+}
+
+fn expense_report(rng: &mut SeededRng, size: usize) -> Result<GeneratedInput, InputError> {
+    let values = expense_values(rng, size, 2020, &[2, 3])?;
+
+    return Ok(GeneratedInput::from_lines(values.iter().map(|v| v.to_string()).collect()));
+}
+
+fn password_database(rng: &mut SeededRng, size: usize) -> Result<GeneratedInput, InputError> {
+    let letters: Vec<char> = ('a'..='z').collect();
+
+    let mut lines = Vec::with_capacity(size);
+    for _ in 0..size {
+        let required_character = *rng.choose(&letters);
+        let length = rng.range(1, 20);
+        let password: String = (0..length)
+            .map(|_| if rng.chance(30) { required_character } else { *rng.choose(&letters) })
+            .collect();
+        let first = rng.range(1, length);
+        let second = rng.range(first, length);
+
+        lines.push(format!("{}-{} {}: {}", first, second, required_character, password));
+    }
+
+    return Ok(GeneratedInput::from_lines(lines));
+}
+
+fn slope_map(rng: &mut SeededRng, size: usize) -> Result<GeneratedInput, InputError> {
+    too_small(size, 1)?;
+
+    let lines = (0..size)
+        .map(|_| (0..31).map(|_| if rng.chance(25) { '#' } else { '.' }).collect())
+        .collect();
+
+    return Ok(GeneratedInput::from_lines(lines));
+}
+
+// Most fields are present and valid; the rest are missing or carry a value that fails validation.
+fn passports(rng: &mut SeededRng, size: usize) -> Result<GeneratedInput, InputError> {
+    let eye_colours = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
+
+    let mut lines = Vec::new();
+    for p in 0..size {
+        let mut fields = Vec::new();
+        for key in &["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid", "cid"] {
+            if !rng.chance(if *key == "cid" { 50 } else { 90 }) {
+                continue;
+            }
+
+            let valid = rng.chance(85);
+            let value = match (*key, valid) {
+                ("byr", true) => rng.range(1920, 2002).to_string(),
+                ("byr", false) => rng.range(1900, 1919).to_string(),
+                ("iyr", true) => rng.range(2010, 2020).to_string(),
+                ("iyr", false) => rng.range(2021, 2030).to_string(),
+                ("eyr", true) => rng.range(2020, 2030).to_string(),
+                ("eyr", false) => rng.range(2000, 2019).to_string(),
+                ("hgt", true) if rng.chance(50) => format!("{}cm", rng.range(150, 193)),
+                ("hgt", true) => format!("{}in", rng.range(59, 76)),
+                ("hgt", false) => rng.range(59, 193).to_string(),
+                ("hcl", true) => format!("#{:06x}", rng.next_u64() % 0x1000000),
+                ("hcl", false) => format!("{:06x}", rng.next_u64() % 0x1000000),
+                ("ecl", true) => rng.choose(&eye_colours).to_string(),
+                ("ecl", false) => String::from("zzz"),
+                ("pid", true) => format!("{:09}", rng.next_u64() % 1_000_000_000),
+                ("pid", false) => format!("{:010}", rng.next_u64() % 10_000_000_000),
+                _ => rng.range(100, 999).to_string(),
+            };
+            fields.push(format!("{}:{}", key, value));
+        }
+        if fields.is_empty() {
+            fields.push(format!("cid:{}", rng.range(100, 999)));
+        }
+        rng.shuffle(&mut fields);
+
+        if p > 0 {
+            lines.push(String::new());
+        }
+        let mut remaining = &fields[..];
+        while !remaining.is_empty() {
+            let line_fields = rng.range(1, remaining.len().min(4));
+            lines.push(remaining[..line_fields].join(" "));
+            remaining = &remaining[line_fields..];
+        }
+    }
+
+    return Ok(GeneratedInput::from_lines(lines));
+}
+
+// A contiguous block of seats with exactly one empty seat inside it, so both parts have answers.
+// There are only 1024 seats, which caps the size.
+fn boarding_passes(rng: &mut SeededRng, size: usize) -> Result<GeneratedInput, InputError> {
+    too_small(size, 2)?;
+    if size > 1023 {
+        return Err(InputError::new(ErrorKind::InvalidParameter, "a plane has room for at most 1023 boarding passes around an empty seat").with_text(&size.to_string()));
+    }
+
+    let first_seat = rng.range(0, 1023 - size);
+    let empty_seat = rng.range(first_seat + 1, first_seat + size - 1);
+    let mut seats: Vec<usize> = (first_seat..=first_seat + size).filter(|s| *s != empty_seat).collect();
+    rng.shuffle(&mut seats);
+
+    let lines = seats.iter()
+        .map(|s| {
+            let row: String = (0..7).rev().map(|bit| if (s >> 3) & (1 << bit) == 0 { 'F' } else { 'B' }).collect();
+            let column: String = (0..3).rev().map(|bit| if s & (1 << bit) == 0 { 'L' } else { 'R' }).collect();
+            row + &column
+        })
+        .collect();
+
+    return Ok(GeneratedInput::from_lines(lines));
+}
+
+// Each group shares a few answers so that both parts count something.
+fn customs_groups(rng: &mut SeededRng, size: usize) -> Result<GeneratedInput, InputError> {
+    let letters: Vec<char> = ('a'..='z').collect();
+
+    let mut lines = Vec::new();
+    for g in 0..size {
+        if g > 0 {
+            lines.push(String::new());
+        }
+
+        let shared: Vec<char> = (0..rng.range(0, 3)).map(|_| *rng.choose(&letters)).collect();
+        for _ in 0..rng.range(1, 5) {
+            let mut answers = shared.clone();
+            for _ in 0..rng.range(1, 8) {
+                answers.push(*rng.choose(&letters));
+            }
+            answers.sort();
+            answers.dedup();
+            rng.shuffle(&mut answers);
+            lines.push(answers.iter().collect());
+        }
+    }
+
+    return Ok(GeneratedInput::from_lines(lines));
+}
+
+// Bags are split into a handful of levels and only contain bags from deeper levels, so the rules
+// are acyclic and the contained-bag totals stay well within range. "shiny gold" sits in a middle
+// level and is held by a top-level bag, so both parts have non-trivial answers.
+fn bag_rules(rng: &mut SeededRng, size: usize) -> Result<GeneratedInput, InputError> {
+    too_small(size, 3)?;
+
+    let adjectives = [
+        "bright", "clear", "dark", "dim", "dotted", "drab", "dull", "faded", "light", "mirrored",
+        "muted", "pale", "plaid", "posh", "shiny", "striped", "vibrant", "wavy",
+    ];
+    let colours = [
+        "aqua", "beige", "black", "blue", "bronze", "brown", "chartreuse", "coral", "crimson", "cyan",
+        "fuchsia", "gold", "gray", "green", "indigo", "lavender", "lime", "magenta", "maroon", "olive",
+        "orange", "plum", "purple", "red", "salmon", "silver", "tan", "teal", "tomato", "turquoise",
+        "violet", "white", "yellow",
+    ];
+    let combinations = adjectives.len() * colours.len();
+    let mut names: Vec<String> = (0..size)
+        .map(|i| {
+            let name = format!("{} {}", adjectives[i % adjectives.len()], colours[(i / adjectives.len()) % colours.len()]);
+            if i < combinations { name } else { format!("{}{}", name, i / combinations) }
+        })
+        .collect();
+    rng.shuffle(&mut names);
+
+    let levels = size.min(8);
+    let level_start = |level: usize| (level * size).div_ceil(levels);
+    let level_of = |index: usize| (index * levels) / size;
+
+    let shiny_gold_index = level_start(levels / 2);
+    match names.iter().position(|n| n == "shiny gold") {
+        Some(i) => names.swap(i, shiny_gold_index),
+        None => names[shiny_gold_index] = String::from("shiny gold"),
+    }
+
+    let mut lines = Vec::with_capacity(size);
+    for (i, name) in names.iter().enumerate() {
+        let next_level_start = level_start(level_of(i) + 1);
+        if next_level_start >= size {
+            lines.push(format!("{} bags contain no other bags.", name));
+            continue;
+        }
+
+        let mut contents: Vec<usize> = Vec::new();
+        if i == 0 {
+            contents.push(shiny_gold_index);
+        }
+        for _ in 0..rng.range(1, 4) {
+            let end = rng.range(next_level_start, size - 1);
+            if !contents.contains(&end) {
+                contents.push(end);
+            }
+        }
+
+        let items: Vec<String> = contents.iter()
+            .map(|end| {
+                let count = rng.range(1, 5);
+                format!("{} {} {}", count, names[*end], if count == 1 { "bag" } else { "bags" })
+            })
+            .collect();
+        lines.push(format!("{} bags contain {}.", name, items.join(", ")));
+    }
+    rng.shuffle(&mut lines);
+
+    return Ok(GeneratedInput::from_lines(lines));
+}
+
+// A program that would finish using only forward jumps, with one instruction on its path then
+// turned into a backward jump. The program loops, and swapping that jump for a nop lets it
+// finish, as the puzzle requires.
+fn boot_code(rng: &mut SeededRng, size: usize) -> Result<GeneratedInput, InputError> {
+    too_small(size, 2)?;
+
+    let mut program: Vec<(&str, i64)> = Vec::with_capacity(size);
+    for i in 0..size {
+        let instruction = match rng.range(1, 4) {
+            1 | 2 => ("acc", rng.range(0, 100) as i64 - 50),
+            3 => ("nop", rng.range(0, 20) as i64 - 10),
+            _ => ("jmp", rng.range(1, (size - i).min(5)) as i64),
+        };
+        program.push(instruction);
+    }
+    program[0] = ("acc", rng.range(1, 50) as i64);
+
+    let mut executed = Vec::new();
+    let mut index = 0;
+    while index < size {
+        executed.push(index);
+        index += if program[index].0 == "jmp" { program[index].1 as usize } else { 1 };
+    }
+
+    // The first instruction is an acc, so at least two instructions run. As a nop, the broken
+    // instruction leaves only forward jumps, so the program finishes whatever it replaced.
+    let broken = rng.range(1, executed.len() - 1);
+    let loop_target = executed[rng.range(0, broken - 1)];
+    program[executed[broken]] = ("jmp", loop_target as i64 - executed[broken] as i64);
+
+    let lines = program.iter().map(|(operation, argument)| format!("{} {:+}", operation, argument)).collect();
+
+    return Ok(GeneratedInput::from_lines(lines));
+}
+
+// After a preamble of 25, each number is the sum of two different numbers among the 25 before it,
+// except for one planted number that is instead the sum of an earlier contiguous run. Every number
+// is at least twice the smallest of the 25 before it, so sequences much past puzzle size overflow.
+fn xmas_values(rng: &mut SeededRng, size: usize) -> Result<GeneratedInput, InputError> {
+    let preamble = 25;
+    too_small(size, preamble + 2)?;
+
+    let mut values: Vec<usize> = Vec::with_capacity(size);
+    while values.len() < preamble {
+        let value = rng.range(1, 75);
+        if !values.contains(&value) {
+            values.push(value);
+        }
+    }
+
+    let invalid_index = rng.range(preamble + (size - preamble) / 2, size - 1);
+    let mut target = None;
+    while values.len() < size {
+        let window = &values[values.len() - preamble..];
+        if values.len() == invalid_index {
+            let invalid = contiguous_run_sum_not_in_window(rng, &values, window)?;
+            target = Some(invalid);
+            values.push(invalid);
+            continue;
+        }
+
+        // Summing two of the smallest keeps growth as slow as the puzzle allows.
+        let mut smallest: Vec<usize> = window.to_vec();
+        smallest.sort();
+        smallest.dedup();
+        smallest.truncate(4);
+        if smallest.len() < 2 {
+            return Err(InputError::new(ErrorKind::NoSolution, "the last 25 XMAS numbers are all equal"));
+        }
+        let first = rng.range(0, smallest.len() - 2);
+        let second = rng.range(first + 1, smallest.len() - 1);
+        match smallest[first].checked_add(smallest[second]) {
+            Some(v) => values.push(v),
+            None => return Err(InputError::new(ErrorKind::InvalidParameter, "XMAS numbers overflow at this size").with_text(&size.to_string())),
+        }
+    }
+
+    let mut generated = GeneratedInput::from_lines(values.iter().map(|v| v.to_string()).collect());
+    generated.params = vec!(
+        (String::from("preamble"), preamble.to_string()),
+        (String::from("target"), target.unwrap_or_default().to_string()),
+    );
+
+    return Ok(generated);
+}
+
+fn contiguous_run_sum_not_in_window(rng: &mut SeededRng, earlier: &[usize], window: &[usize]) -> Result<usize, InputError> {
+    let is_pair_sum = |value: usize| window.iter()
+        .any(|a| window.iter().any(|b| a != b && a.checked_add(*b) == Some(value)));
+
+    let offset = rng.range(0, earlier.len() - 1);
+    for i in 0..earlier.len() {
+        let start = (offset + i) % earlier.len();
+        let mut sum = earlier[start];
+        for value in &earlier[start + 1..(start + 4).min(earlier.len())] {
+            sum = match sum.checked_add(*value) {
+                Some(s) => s,
+                None => break,
+            };
+            if !is_pair_sum(sum) {
+                return Ok(sum);
+            }
+        }
+    }
+
+    return Err(InputError::new(ErrorKind::NoSolution, "no contiguous run gives a number outside the XMAS rule"));
+}
+
+fn joltage_adapters(rng: &mut SeededRng, size: usize) -> Result<GeneratedInput, InputError> {
+    let mut joltage = 0;
+    let mut adapters = Vec::with_capacity(size);
+    for _ in 0..size {
+        joltage += if rng.chance(65) { 1 } else { 3 };
+        adapters.push(joltage);
+    }
+    rng.shuffle(&mut adapters);
+
+    return Ok(GeneratedInput::from_lines(adapters.iter().map(|a| a.to_string()).collect()));
+}
+
+#[cfg(test)]
+mod tests {
+    fn solve(day: usize, seed: u64, size: usize) -> Vec<crate::ResultHashMap> {
+        let generated = super::generate(day, seed, size).unwrap();
+        let mut challenge = (crate::challenge_by_day(&day.to_string()).unwrap())();
+        challenge.set_params(&generated.params).unwrap();
+
+        return vec!(challenge.run_str(1, &generated.text), challenge.run_str(2, &generated.text));
+    }
+
+    #[test]
+    fn generated_inputs_are_solvable() {
+        for day in 1..=crate::challenge_count() {
+            let size = super::puzzle_size(day).unwrap();
+            for seed in 0..5 {
+                for (part, result) in solve(day, seed, size).iter().enumerate() {
+                    assert!(result.is_ok(), "day {} part {} seed {}: {:?}", day, part + 1, seed, result.as_ref().err());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn generation_is_reproducible() {
+        for day in 1..=crate::challenge_count() {
+            let first = super::generate(day, 7, 50).map(|g| g.text);
+            let second = super::generate(day, 7, 50).map(|g| g.text);
+
+            assert_eq!(first.ok(), second.ok());
+        }
+        assert_ne!(super::generate(3, 1, 10).unwrap().text, super::generate(3, 2, 10).unwrap().text);
+    }
+
+    #[test]
+    fn planted_answers_are_found() {
+        let mut rng = super::SeededRng::new(1);
+        let values = super::expense_values(&mut rng, 100, 2020, &[2, 3, 4]).unwrap();
+        assert_eq!(100, values.len());
+        assert!(values.iter().all(|v| *v >= 1 && *v < 2020));
+
+        let generated = super::generate(9, 3, 200).unwrap();
+        let answers = solve(9, 3, 200);
+        assert_eq!(generated.params[1].1, answers[0].as_ref().unwrap()["target number"].to_string());
+    }
+
+    #[test]
+    fn sizes_outside_a_format_are_errors() {
+        assert!(super::generate(5, 1, 1024).is_err());
+        assert!(super::generate(9, 1, 10).is_err());
+        assert!(super::generate(9, 1, 100_000).is_err());
+        assert!(super::generate(11, 1, 10).is_err());
+        assert!(super::generate(1, 1, 4).is_err());
+    }
+
+    #[test]
+    fn hundred_times_puzzle_size() {
+        for day in &[1, 2, 3, 6, 7, 8, 10] {
+            let size = super::puzzle_size(*day).unwrap() * 100;
+            let generated = super::generate(*day, 2020, size).unwrap();
+
+            assert!(crate::PuzzleInput::from_text(&generated.text).lines().len() >= size);
+        }
+    }
+}
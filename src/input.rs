@@ -0,0 +1,223 @@
+use crate::ErrorKind;
+use crate::InputError;
+
+// Puzzle input as lines, normalised the same way however it was read: a leading UTF-8 byte order
+// mark is dropped, CRLF endings become LF, and a missing final newline makes no difference.
+pub struct PuzzleInput {
+    lines: Vec<String>,
+}
+
+// A run of non-blank lines; `first_line` is the 1-based line number of `lines[0]`.
+pub struct Group<'a> {
+    pub first_line: usize,
+    pub lines: &'a [String],
+}
+
+// A whitespace-separated token with its 1-based line and character column.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub line: usize,
+    pub column: usize,
+    pub text: &'a str,
+}
+
+impl PuzzleInput {
+    pub fn from_lines(lines: Vec<String>) -> PuzzleInput {
+        let mut lines = lines;
+        if let Some(first) = lines.first_mut() {
+            if let Some(stripped) = first.strip_prefix('\u{feff}') {
+                *first = String::from(stripped);
+            }
+        }
+        for line in &mut lines {
+            if line.ends_with('\r') {
+                line.pop();
+            }
+        }
+
+        return PuzzleInput { lines: lines };
+    }
+
+    pub fn from_text(text: &str) -> PuzzleInput {
+        return PuzzleInput::from_lines(text.lines().map(String::from).collect());
+    }
+
+    pub fn from_reader(reader: impl std::io::BufRead) -> Result<PuzzleInput, InputError> {
+        return Ok(PuzzleInput::from_lines(crate::reader_lines_to_string_vec(reader)?));
+    }
+
+    // A filepath of "-" reads from stdin.
+    pub fn from_file(filepath: &str) -> Result<PuzzleInput, InputError> {
+        return Ok(PuzzleInput::from_lines(crate::file_lines_to_string_vec(filepath)?));
+    }
+
+    pub fn lines(&self) -> &Vec<String> {
+        return &self.lines;
+    }
+
+    // Lines holding only whitespace separate groups like empty ones. Consecutive blank lines and
+    // blank lines at either end never produce empty groups.
+    pub fn groups(&self) -> Vec<Group<'_>> {
+        let mut groups = Vec::new();
+        let mut start = 0;
+        for (i, line) in self.lines.iter().enumerate() {
+            if line.trim().is_empty() {
+                if i > start {
+                    groups.push(Group { first_line: start + 1, lines: &self.lines[start..i] });
+                }
+                start = i + 1;
+            }
+        }
+
+        if self.lines.len() > start {
+            groups.push(Group { first_line: start + 1, lines: &self.lines[start..] });
+        }
+
+        return groups;
+    }
+
+    pub fn tokens(&self) -> Vec<Token<'_>> {
+        return line_tokens(&self.lines, 1);
+    }
+
+    // One number per line. Trailing blank lines are ignored; any other line that is not a number
+    // is an error pointing at it.
+    pub fn integers<T>(&self) -> Result<Vec<T>, InputError>
+    where
+        T: std::str::FromStr,
+        T::Err: std::error::Error + Send + Sync + 'static,
+    {
+        let mut values = Vec::with_capacity(self.lines.len());
+        for (i, line) in self.content_lines().iter().enumerate() {
+            values.push(
+                line.parse::<T>().map_err(|e|
+                    InputError::new(ErrorKind::InvalidNumber, "expected a whole number")
+                        .with_source(e)
+                        .with_text(line)
+                        .with_line_text(line)
+                        .at_column(1)
+                        .at_line(i + 1)
+                )?
+            );
+        }
+
+        return Ok(values);
+    }
+
+    // A rectangular grid of characters, one row per line. Trailing blank lines are ignored.
+    pub fn grid(&self) -> Result<Vec<Vec<char>>, InputError> {
+        let mut grid: Vec<Vec<char>> = Vec::new();
+        for (i, line) in self.content_lines().iter().enumerate() {
+            let row: Vec<char> = line.chars().collect();
+            let malformed = |message: &str| InputError::new(ErrorKind::MalformedMap, message)
+                .with_text(line)
+                .with_line_text(line)
+                .at_column(1)
+                .at_line(i + 1);
+
+            if row.is_empty() {
+                return Err(malformed("expected a non-empty grid row"));
+            }
+            if let Some(first_row) = grid.first() {
+                if row.len() != first_row.len() {
+                    return Err(malformed(&format!("expected a row {} characters wide", first_row.len())));
+                }
+            }
+
+            grid.push(row);
+        }
+
+        return Ok(grid);
+    }
+
+    // The lines up to the last one with anything but whitespace on it.
+    pub fn content_lines(&self) -> &[String] {
+        let content_len = self.lines.iter().rposition(|l| !l.trim().is_empty()).map_or(0, |i| i + 1);
+
+        return &self.lines[..content_len];
+    }
+}
+
+impl<'a> Group<'a> {
+    pub fn tokens(&self) -> Vec<Token<'a>> {
+        return line_tokens(self.lines, self.first_line);
+    }
+}
+
+fn line_tokens(lines: &[String], first_line: usize) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let mut token_start: Option<(usize, usize)> = None;
+        for (column, (byte_index, c)) in line.char_indices().enumerate() {
+            match (c.is_whitespace(), token_start) {
+                (false, None) => token_start = Some((column, byte_index)),
+                (true, Some((start_column, start_byte))) => {
+                    tokens.push(Token { line: first_line + i, column: start_column + 1, text: &line[start_byte..byte_index] });
+                    token_start = None;
+                },
+                _ => {},
+            }
+        }
+
+        if let Some((start_column, start_byte)) = token_start {
+            tokens.push(Token { line: first_line + i, column: start_column + 1, text: &line[start_byte..] });
+        }
+    }
+
+    return tokens;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PuzzleInput;
+
+    #[test]
+    fn line_endings_are_normalised() {
+        let expected = vec!(String::from("a"), String::from("b"));
+
+        assert_eq!(&expected, PuzzleInput::from_text("a\nb\n").lines());
+        assert_eq!(&expected, PuzzleInput::from_text("a\nb").lines());
+        assert_eq!(&expected, PuzzleInput::from_text("a\r\nb\r\n").lines());
+        assert_eq!(&expected, PuzzleInput::from_text("\u{feff}a\r\nb").lines());
+        assert_eq!(&expected, PuzzleInput::from_reader(&b"\xef\xbb\xbfa\r\nb\r"[..]).unwrap().lines());
+    }
+
+    #[test]
+    fn groups_skip_extra_blank_lines() {
+        let input = PuzzleInput::from_text("\nab\nc\n\n\nd\n\n");
+        let groups = input.groups();
+
+        assert_eq!(2, groups.len());
+        assert_eq!(2, groups[0].first_line);
+        assert_eq!(&[String::from("ab"), String::from("c")], groups[0].lines);
+        assert_eq!(6, groups[1].first_line);
+        assert_eq!(&[String::from("d")], groups[1].lines);
+
+        let input = PuzzleInput::from_text("ab\n  \nc\n\t\n");
+        assert_eq!(vec!(1, 3), input.groups().iter().map(|g| g.first_line).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn tokens_have_positions() {
+        let input = PuzzleInput::from_text("ecl:gry  pid:1\n\n é:x");
+        let tokens: Vec<(usize, usize, &str)> = input.tokens().iter().map(|t| (t.line, t.column, t.text)).collect();
+
+        assert_eq!(vec!((1, 1, "ecl:gry"), (1, 10, "pid:1"), (3, 2, "é:x")), tokens);
+        assert_eq!(3, input.groups()[1].tokens()[0].line);
+    }
+
+    #[test]
+    fn integers_ignore_trailing_blank_lines() {
+        assert_eq!(vec!(1, -2, 3), PuzzleInput::from_text("1\r\n-2\r\n3\r\n\r\n").integers::<i64>().unwrap());
+        assert_eq!(vec!(1, 2), PuzzleInput::from_text("1\n2\n \t\n\n").integers::<i64>().unwrap());
+
+        let error = PuzzleInput::from_text("1\nx\n3").integers::<i64>().unwrap_err();
+        assert_eq!(Some(2), error.line());
+    }
+
+    #[test]
+    fn grid_must_be_rectangular() {
+        assert_eq!(vec!(vec!('.', '#'), vec!('#', '.')), PuzzleInput::from_text(".#\n#.\n").grid().unwrap());
+        assert_eq!(Some(2), PuzzleInput::from_text(".#\n#\n").grid().unwrap_err().line());
+    }
+}
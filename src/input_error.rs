@@ -1,6 +1,233 @@
-#[derive(Debug)]
-pub enum InputError {
-    Io(std::io::Error),
-    Parse(std::num::ParseIntError),
-    Regex(regex::Error),
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    Io,
+    InvalidNumber,
+    InvalidPattern,
+    UnknownDay,
+    UnknownPart,
+    UnknownParameter,
+    UnknownImplementation,
+    InvalidParameter,
+    InputMismatch,
+    MalformedExpense,
+    MalformedPassword,
+    MalformedMap,
+    MalformedPassport,
+    MalformedBoardingPass,
+    MalformedBagRule,
+    MalformedInstruction,
+    MalformedBaseline,
+    MalformedLedger,
+    EmptyInput,
+    NoSolution,
+    Panic,
+    Failed,
+}
+
+impl std::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let description = match self {
+            ErrorKind::Io => "i/o error",
+            ErrorKind::InvalidNumber => "invalid number",
+            ErrorKind::InvalidPattern => "invalid pattern",
+            ErrorKind::UnknownDay => "unknown day",
+            ErrorKind::UnknownPart => "unknown part",
+            ErrorKind::UnknownParameter => "unknown parameter",
+            ErrorKind::UnknownImplementation => "unknown implementation",
+            ErrorKind::InvalidParameter => "invalid parameter",
+            ErrorKind::InputMismatch => "input mismatch",
+            ErrorKind::MalformedExpense => "malformed expense",
+            ErrorKind::MalformedPassword => "malformed password entry",
+            ErrorKind::MalformedMap => "malformed map",
+            ErrorKind::MalformedPassport => "malformed passport",
+            ErrorKind::MalformedBoardingPass => "malformed boarding pass",
+            ErrorKind::MalformedBagRule => "malformed bag rule",
+            ErrorKind::MalformedInstruction => "malformed instruction",
+            ErrorKind::MalformedBaseline => "malformed baseline",
+            ErrorKind::MalformedLedger => "malformed answer ledger",
+            ErrorKind::EmptyInput => "empty input",
+            ErrorKind::NoSolution => "no solution",
+            ErrorKind::Panic => "panic",
+            ErrorKind::Failed => "failed",
+        };
+
+        return write!(f, "{}", description);
+    }
+}
+
+// Location fields are filled in as the error travels outwards: a domain parser knows the column
+// and offending text, the challenge knows the line, and the caller that read the input knows the file.
+#[derive(Clone, Debug)]
+pub struct InputError {
+    kind: ErrorKind,
+    details: Box<ErrorDetails>,
+}
+
+// Boxed so that results carrying an `InputError` stay small.
+#[derive(Clone, Debug)]
+struct ErrorDetails {
+    message: String,
+    file: Option<String>,
+    line: Option<usize>,
+    column: Option<usize>,
+    text: Option<String>,
+    line_text: Option<String>,
+    source: Option<std::sync::Arc<dyn std::error::Error + Send + Sync>>,
+}
+
+impl InputError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> InputError {
+        return InputError {
+            kind: kind,
+            details: Box::new(ErrorDetails {
+                message: message.into(),
+                file: None,
+                line: None,
+                column: None,
+                text: None,
+                line_text: None,
+                source: None,
+            }),
+        };
+    }
+
+    pub fn with_source(mut self, source: impl std::error::Error + Send + Sync + 'static) -> InputError {
+        self.details.source = Some(std::sync::Arc::new(source));
+        return self;
+    }
+
+    pub fn with_text(mut self, text: &str) -> InputError {
+        self.details.text = Some(String::from(text));
+        return self;
+    }
+
+    // The whole input line that the column points into, quoted when rendering a diagnostic.
+    pub fn with_line_text(mut self, line_text: &str) -> InputError {
+        self.details.line_text = Some(String::from(line_text));
+        return self;
+    }
+
+    // Columns are 1-based character positions within the line.
+    pub fn at_column(mut self, column: usize) -> InputError {
+        self.details.column = Some(column);
+        return self;
+    }
+
+    // Lines are 1-based; an inner layer's line number is kept.
+    pub fn at_line(mut self, line: usize) -> InputError {
+        self.details.line = self.details.line.or(Some(line));
+        return self;
+    }
+
+    // "-" is the filepath that reads stdin, so it is named as such.
+    pub fn in_file(mut self, file: &str) -> InputError {
+        if self.details.file.is_none() {
+            self.details.file = Some(String::from(if file == "-" { "<stdin>" } else { file }));
+        }
+        return self;
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        return self.kind;
+    }
+
+    pub fn message(&self) -> &str {
+        return &self.details.message;
+    }
+
+    pub fn file(&self) -> Option<&str> {
+        return self.details.file.as_deref();
+    }
+
+    pub fn line(&self) -> Option<usize> {
+        return self.details.line;
+    }
+
+    pub fn column(&self) -> Option<usize> {
+        return self.details.column;
+    }
+
+    pub fn text(&self) -> Option<&str> {
+        return self.details.text.as_deref();
+    }
+
+    pub fn line_text(&self) -> Option<&str> {
+        return self.details.line_text.as_deref();
+    }
+}
+
+impl std::fmt::Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let location: Vec<String> = [
+            self.file().map(String::from),
+            self.line().map(|l| l.to_string()),
+            self.column().map(|c| c.to_string()),
+        ]
+        .iter()
+        .flatten()
+        .cloned()
+        .collect();
+
+        if !location.is_empty() {
+            write!(f, "{}: ", location.join(":"))?;
+        }
+        write!(f, "{}", self.message())?;
+        if let Some(text) = self.text().filter(|t| !t.is_empty()) {
+            write!(f, " (found {:?})", text)?;
+        }
+
+        return Ok(());
+    }
+}
+
+impl std::error::Error for InputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        return self.details.source.as_ref().map(|s| s.as_ref() as &(dyn std::error::Error + 'static));
+    }
+}
+
+impl From<std::io::Error> for InputError {
+    fn from(error: std::io::Error) -> InputError {
+        return InputError::new(ErrorKind::Io, "could not read input").with_source(error);
+    }
+}
+
+impl From<std::num::ParseIntError> for InputError {
+    fn from(error: std::num::ParseIntError) -> InputError {
+        return InputError::new(ErrorKind::InvalidNumber, "could not parse number").with_source(error);
+    }
+}
+
+impl From<regex::Error> for InputError {
+    fn from(error: regex::Error) -> InputError {
+        return InputError::new(ErrorKind::InvalidPattern, "could not compile pattern").with_source(error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ErrorKind;
+    use super::InputError;
+
+    #[test]
+    fn display_includes_location_and_text() {
+        let error = InputError::new(ErrorKind::MalformedPassword, "expected a character range")
+            .with_text("1x3")
+            .at_column(1)
+            .at_line(4)
+            .at_line(9)
+            .in_file("passwords.txt");
+
+        assert_eq!("passwords.txt:4:1: expected a character range (found \"1x3\")", error.to_string());
+    }
+
+    #[test]
+    fn source_is_chained() {
+        use std::error::Error;
+
+        let error = InputError::from("x".parse::<usize>().unwrap_err());
+
+        assert_eq!(ErrorKind::InvalidNumber, error.kind());
+        assert_eq!("invalid digit found in string", error.source().unwrap().to_string());
+    }
 }
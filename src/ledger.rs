@@ -0,0 +1,219 @@
+use crate::runner::PartRun;
+use crate::Answer;
+use crate::ErrorKind;
+use crate::InputError;
+
+// Expected answers keyed by day and part, kept as printed text so any answer type compares alike.
+pub struct AnswerLedger {
+    expected: std::collections::BTreeMap<(usize, usize), String>,
+}
+
+impl AnswerLedger {
+    pub fn new() -> AnswerLedger {
+        return AnswerLedger { expected: std::collections::BTreeMap::new() };
+    }
+
+    // Lines hold a day, a part and the expected answer; blank lines and # comments are skipped.
+    pub fn load(filepath: &str) -> Result<AnswerLedger, InputError> {
+        let mut ledger = AnswerLedger::new();
+        for (i, line) in crate::file_lines_to_string_vec(filepath)?.iter().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            let entry = AnswerLedger::parse_line(trimmed).map_err(|e| e.with_line_text(line).at_line(i + 1).in_file(filepath))?;
+            if ledger.expected.insert((entry.0, entry.1), entry.2).is_some() {
+                return Err(
+                    InputError::new(ErrorKind::MalformedLedger, format!("day {} part {} is listed more than once", entry.0, entry.1))
+                        .with_line_text(line)
+                        .at_line(i + 1)
+                        .in_file(filepath)
+                );
+            }
+        }
+
+        return Ok(ledger);
+    }
+
+    // Fields may be separated by any run of whitespace, so columns can be aligned; the answer is
+    // the rest of the line and may itself hold spaces.
+    fn parse_line(line: &str) -> Result<(usize, usize, String), InputError> {
+        let malformed = || InputError::new(ErrorKind::MalformedLedger, "expected day, part and expected answer").with_text(line).at_column(1);
+        let (day_text, rest) = line.split_once(char::is_whitespace).ok_or_else(malformed)?;
+        let rest = rest.trim_start();
+        let (part_text, answer) = rest.split_once(char::is_whitespace).ok_or_else(malformed)?;
+        let answer = answer.trim();
+        if answer.is_empty() {
+            return Err(malformed());
+        }
+
+        let day = day_text.parse::<usize>().map_err(|e| InputError::from(e).with_text(day_text).at_column(1))?;
+        let part_column = line[..line.len() - rest.len()].chars().count() + 1;
+        let part = part_text.parse::<usize>().map_err(|e| InputError::from(e).with_text(part_text).at_column(part_column))?;
+
+        return Ok((day, part, answer.to_string()));
+    }
+
+    pub fn insert(&mut self, day: usize, part: usize, expected: &str) {
+        self.expected.insert((day, part), expected.to_string());
+    }
+
+    pub fn get(&self, day: usize, part: usize) -> Option<&str> {
+        return self.expected.get(&(day, part)).map(|e| e.as_str());
+    }
+}
+
+impl Default for AnswerLedger {
+    fn default() -> AnswerLedger {
+        return AnswerLedger::new();
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Verdict {
+    Match,
+    Mismatch { expected: String },
+    Missing,
+    Failed(InputError),
+}
+
+pub struct Verification {
+    pub day: usize,
+    pub part: usize,
+    pub actual: Option<String>,
+    pub verdict: Verdict,
+}
+
+impl Verification {
+    pub fn passed(&self) -> bool {
+        return match self.verdict {
+            Verdict::Match | Verdict::Missing => true,
+            Verdict::Mismatch { .. } | Verdict::Failed(_) => false,
+        };
+    }
+}
+
+// A part's answer is its single value, or every value in key order when it reports several.
+pub fn part_answer(answers: &std::collections::HashMap<String, Answer>) -> String {
+    let mut keys: Vec<&String> = answers.keys().collect();
+    keys.sort();
+
+    return keys.iter().map(|k| answers[*k].to_string()).collect::<Vec<String>>().join(", ");
+}
+
+pub fn verify_runs(ledger: &AnswerLedger, runs: &[PartRun]) -> Vec<Verification> {
+    let mut verifications = Vec::new();
+    for run in runs {
+        let (actual, verdict) = match &run.result {
+            Ok(answers) => {
+                let actual = part_answer(answers);
+                let verdict = match ledger.get(run.day, run.part) {
+                    Some(expected) if expected == actual => Verdict::Match,
+                    Some(expected) => Verdict::Mismatch { expected: expected.to_string() },
+                    None => Verdict::Missing,
+                };
+                (Some(actual), verdict)
+            },
+            Err(e) => (None, Verdict::Failed(e.clone())),
+        };
+
+        verifications.push(Verification { day: run.day, part: run.part, actual: actual, verdict: verdict });
+    }
+
+    return verifications;
+}
+
+// Runs every implemented part of every registered day against its default input.
+pub fn verify_all(ledger: &AnswerLedger) -> Vec<Verification> {
+    let mut runs = Vec::new();
+    for entry in crate::registry::Registry::new().entries() {
+        runs.extend(crate::runner::run_day(entry.day, entry.metadata.parts, &[], &[]));
+    }
+
+    return verify_runs(ledger, &runs);
+}
+
+// Runs every implemented part of one day, on another input file or with other parameters.
+pub fn verify_day(ledger: &AnswerLedger, day: usize, args: &[String], params: &[(String, String)]) -> Result<Vec<Verification>, InputError> {
+    let registry = crate::registry::Registry::new();
+    let runs = crate::runner::run_day(day, registry.get(day)?.metadata.parts, args, params);
+
+    return Ok(verify_runs(ledger, &runs));
+}
+
+pub fn print_verifications(out: &mut dyn std::io::Write, verifications: &[Verification]) -> std::io::Result<()> {
+    for v in verifications {
+        let actual = v.actual.clone().unwrap_or_default();
+        match &v.verdict {
+            Verdict::Match => writeln!(out, "{:>2}  {}  match     {}", v.day, v.part, actual)?,
+            Verdict::Mismatch { expected } => writeln!(out, "{:>2}  {}  MISMATCH  {} (expected {})", v.day, v.part, actual, expected)?,
+            Verdict::Missing => writeln!(out, "{:>2}  {}  missing   {} (no expected answer)", v.day, v.part, actual)?,
+            Verdict::Failed(e) => writeln!(out, "{:>2}  {}  error     {}", v.day, v.part, crate::runner::error_message(e))?,
+        }
+    }
+
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AnswerLedger;
+    use super::Verdict;
+
+    #[test]
+    fn shipped_ledger_matches_every_part() {
+        let ledger = AnswerLedger::load("resources/answers.txt").unwrap();
+        let verifications = super::verify_all(&ledger);
+
+        assert_eq!(2 * crate::challenge_count(), verifications.len());
+        for v in &verifications {
+            assert!(matches!(v.verdict, Verdict::Match), "day {} part {} did not match", v.day, v.part);
+        }
+    }
+
+    #[test]
+    fn reports_mismatches_and_missing_entries() {
+        let mut ledger = AnswerLedger::new();
+        ledger.insert(1, 1, "1");
+
+        let runs = crate::runner::run_day(1, &[1, 2], &[], &[]);
+        let verifications = super::verify_runs(&ledger, &runs);
+
+        assert!(matches!(&verifications[0].verdict, Verdict::Mismatch { expected } if expected == "1"));
+        assert!(matches!(verifications[1].verdict, Verdict::Missing));
+        assert!(!verifications[0].passed() && verifications[1].passed());
+
+        let mut printed = Vec::new();
+        super::print_verifications(&mut printed, &verifications).unwrap();
+        assert!(String::from_utf8(printed).unwrap().starts_with(" 1  1  MISMATCH  121396 (expected 1)\n"));
+    }
+
+    #[test]
+    fn verifies_a_day_on_another_input_with_params() {
+        let mut ledger = AnswerLedger::new();
+        ledger.insert(1, 1, "358314");
+
+        let args = [String::from("resources/examples/day1/example.txt")];
+        let params = [(String::from("target"), String::from("1345"))];
+        let verifications = super::verify_day(&ledger, 1, &args, &params).unwrap();
+
+        assert!(matches!(verifications[0].verdict, Verdict::Match));
+        assert!(matches!(verifications[1].verdict, Verdict::Failed(_)));
+    }
+
+    #[test]
+    fn malformed_lines_are_located() {
+        let error = AnswerLedger::parse_line("1 x 42").unwrap_err();
+        assert_eq!(Some(3), error.column());
+        assert!(AnswerLedger::parse_line("1 1").is_err());
+        assert_eq!(Some("shiny gold"), AnswerLedger::parse_line("7 1 shiny gold").ok().as_ref().map(|e| e.2.as_str()));
+        assert_eq!(Some(5), AnswerLedger::parse_line("1   x 42").unwrap_err().column());
+    }
+
+    #[test]
+    fn fields_may_be_aligned_with_extra_whitespace() {
+        assert_eq!((1, 1, String::from("514579")), AnswerLedger::parse_line("1  1 514579").unwrap());
+        assert_eq!((10, 2, String::from("shiny  gold")), AnswerLedger::parse_line("10\t2   shiny  gold").unwrap());
+    }
+}
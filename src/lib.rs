@@ -1,6 +1,22 @@
+// The codebase's own style: explicit `return`, `field: field` initialisers and `&Vec` parameters.
+#![allow(clippy::needless_return)]
+#![allow(clippy::redundant_field_names)]
+#![allow(clippy::ptr_arg)]
+
 mod input_error;
+mod answer;
+mod amount;
 
 pub mod challenges;
+pub mod runner;
+pub mod bench;
+pub mod output;
+pub mod registry;
+pub mod diagnostic;
+pub mod input;
+pub mod ledger;
+pub mod generator;
+pub mod differential;
 
 mod expense_report;
 mod password_database;
@@ -8,60 +24,164 @@ mod slope_map;
 mod passport;
 pub mod binary_partitioner;
 mod directed_graph;
+mod bag_rule;
 mod boot_code_computer;
+mod n_ary_tree;
 
+pub use crate::input_error::ErrorKind;
 pub use crate::input_error::InputError;
+pub use crate::answer::Answer;
+pub use crate::amount::Amount;
+pub use crate::amount::Product;
+pub use crate::input::PuzzleInput;
 
-pub type ResultHashMap = Result<std::collections::HashMap<String, usize>, InputError>;
-pub type ChallengeFn = fn(&[String]) -> ResultHashMap;
-pub type ResultChallenge = Result<fn() -> challenges::Challenge, InputError>;
+pub type ResultHashMap = Result<std::collections::HashMap<String, Answer>, InputError>;
+pub type ResultChallenge = Result<fn() -> Box<dyn challenges::DynChallenge>, InputError>;
 
+pub use expense_report::ExpenseEntry;
+pub use expense_report::ExpenseIndex;
+pub use expense_report::ExpenseMatch;
 pub use expense_report::ExpenseReport;
+pub use expense_report::EntrySelection;
 pub use password_database::PasswordDatabase;
+pub use password_database::PasswordDatabaseEntry;
 pub use slope_map::MovementPath;
 pub use slope_map::SlopeMap;
 pub use passport::Passport;
 pub use directed_graph::DirectedGraph;
+pub use bag_rule::BagRule;
 pub use boot_code_computer::BootCodeComputer;
+pub use boot_code_computer::Instruction;
+pub use binary_partitioner::BoardingPass;
 
 pub fn challenge_by_day(day: &str) -> ResultChallenge {
-    let challenges = [
-        challenges::day1::challenge,
-        challenges::day2::challenge,
-        challenges::day3::challenge,
-        challenges::day4::challenge,
-        challenges::day5::challenge,
-        challenges::day6::challenge,
-        challenges::day7::challenge,
-        challenges::day8::challenge,
-        challenges::day9::challenge,
-        challenges::day10::challenge,
-    ];
-
-    let day_index = day
-        .parse::<usize>()
-        .map_err(InputError::Parse)? - 1;
-
-    return Ok(challenges[day_index]);
+    return Ok(registry::Registry::new().get_by_name(day)?.constructor());
+}
+
+pub fn challenge_count() -> usize {
+    return registry::Registry::new().len();
 }
 
+// A filepath of "-" reads from stdin instead of the filesystem.
 pub fn file_lines_to_string_vec(filepath: &str) -> Result<Vec<String>, InputError> {
-    use std::io::BufRead;
+    if filepath == "-" {
+        return reader_lines_to_string_vec(std::io::stdin().lock()).map_err(|e| e.in_file(filepath));
+    }
 
     let reader = std::io::BufReader::new(
-        std::fs::File::open(filepath).map_err(InputError::Io)?
+        std::fs::File::open(filepath).map_err(|e| InputError::from(e).in_file(filepath))?
     );
+
+    return reader_lines_to_string_vec(reader).map_err(|e| e.in_file(filepath));
+}
+
+pub fn reader_lines_to_string_vec(reader: impl std::io::BufRead) -> Result<Vec<String>, InputError> {
     let mut lines: Vec<String> = Vec::new();
     for line in reader.lines() {
-        lines.push(line.map_err(InputError::Io)?);
+        lines.push(line?);
     }
 
     return Ok(lines);
 }
 
-pub fn print_hash_map(result: std::collections::HashMap<String, usize>) {
+pub fn solve_str(day: usize, part: usize, input: &str) -> ResultHashMap {
+    return registry::Registry::new().get(day)?.create().run_str(part, input);
+}
+
+pub fn solve_reader(day: usize, part: usize, mut reader: impl std::io::BufRead) -> ResultHashMap {
+    return registry::Registry::new().get(day)?.create().run_reader(part, &mut reader);
+}
+
+pub fn print_hash_map(result: std::collections::HashMap<String, Answer>) {
+    let mut keys: Vec<&String> = result.keys().collect();
+    keys.sort();
+
     println!("Result:");
-    for (key, value) in result {
-        println!("  {}: {}", key, value);
+    for key in keys {
+        println!("  {}: {}", key, result[key]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn solve_from_str_and_reader() {
+        let input = "1721\n979\n366\n299\n675\n1456\n";
+
+        let from_str = super::solve_str(1, 1, input).unwrap();
+        let from_reader = super::solve_reader(1, 2, std::io::Cursor::new(input)).unwrap();
+
+        assert_eq!(crate::Answer::Unsigned(514579), from_str["expense report values product"]);
+        assert_eq!(crate::Answer::Unsigned(241861950), from_reader["expense report values product"]);
+    }
+
+    #[test]
+    fn line_endings_do_not_change_answers() {
+        let unix = "abc\n\na\nb\nc\n\nab\nac\n";
+        let windows = "\u{feff}abc\r\n\r\na\r\nb\r\nc\r\n\r\nab\r\nac";
+
+        for part in &[1, 2] {
+            assert_eq!(super::solve_str(6, *part, unix).unwrap(), super::solve_str(6, *part, windows).unwrap());
+        }
+    }
+
+    #[test]
+    fn solve_unknown_day() {
+        assert!(super::solve_str(11, 1, "").is_err());
+    }
+
+    // Hostile input may produce an error or an answer, but never a panic.
+    fn assert_no_panic(name: &str, solve: impl Fn(usize, usize) -> crate::ResultHashMap) {
+        for day in 1..=super::challenge_count() {
+            for part in &[1, 2] {
+                let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| solve(day, *part)));
+
+                assert!(result.is_ok(), "day {} part {} panicked on {}", day, part, name);
+            }
+        }
+    }
+
+    #[test]
+    fn hostile_corpus_never_panics() {
+        let mut filepaths: Vec<std::path::PathBuf> = std::fs::read_dir("resources/hostile").unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        filepaths.sort();
+
+        for filepath in &filepaths {
+            let filepath = filepath.to_str().unwrap();
+            let bytes = std::fs::read(filepath).unwrap();
+
+            assert_no_panic(filepath, |day, part| super::solve_reader(day, part, &bytes[..]));
+        }
+    }
+
+    #[test]
+    fn invalid_utf8_never_panics() {
+        let bytes: &[u8] = b"1721\n\xff\xfe\n\xc3\n";
+
+        assert_no_panic("invalid UTF-8", |day, part| super::solve_reader(day, part, bytes));
+    }
+
+    #[test]
+    fn random_token_soup_never_panics() {
+        let tokens = [
+            "0", "1", "7", "-", "+", " ", ":", "#", ".", ",", "\n", "\n\n", "\t", "\r", "é",
+            "bags contain", "bag", "bags", "no other bags", "shiny gold", "acc", "jmp", "nop",
+            "F", "B", "L", "R", "hgt:", "cm", "in", "18446744073709551615", "-9223372036854775808",
+        ];
+
+        // A fixed linear congruential generator keeps failures reproducible.
+        let mut seed: u64 = 2020;
+        for case in 0..200 {
+            let mut input = String::new();
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            for _ in 0..(seed >> 33) % 40 {
+                seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                input.push_str(tokens[((seed >> 33) % tokens.len() as u64) as usize]);
+            }
+
+            assert_no_panic(&format!("case {}: {:?}", case, input), |day, part| super::solve_str(day, part, &input));
+        }
     }
 }
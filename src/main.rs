@@ -1,12 +1,485 @@
+#![allow(clippy::needless_return)]
+
+use adventofcode2020::ErrorKind;
 use adventofcode2020::InputError;
+use adventofcode2020::challenges::DynChallenge;
+use adventofcode2020::bench;
+use adventofcode2020::diagnostic;
+use adventofcode2020::differential;
+use adventofcode2020::generator;
+use adventofcode2020::ledger;
+use adventofcode2020::output;
+use adventofcode2020::output::OutputFormat;
+use adventofcode2020::registry::Registry;
+use adventofcode2020::runner;
+use clap::Parser;
+use std::io::Write;
+
+/// Advent of Code 2020 challenge runner
+#[derive(Parser)]
+#[command(name = "adventofcode2020", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+
+    /// Output format for answers: text, json, csv, tsv or value
+    #[arg(short, long, global = true, default_value = "text")]
+    format: OutputFormat,
+}
+
+#[derive(clap::Subcommand)]
+enum Command {
+    /// Run a single day, or one part of it
+    Run {
+        /// Day to run
+        #[arg(value_parser = parse_day)]
+        day: usize,
+
+        /// Part to run; both parts are run when omitted
+        #[arg(value_parser = parse_part)]
+        part: Option<usize>,
+
+        /// Input file to use instead of the day's default input, or - for stdin
+        #[arg(short, long)]
+        input: Option<String>,
+
+        /// Override a challenge parameter, e.g. --param target=1500
+        #[arg(short, long = "param", value_parser = parse_param)]
+        params: Vec<(String, String)>,
+    },
+    /// List every registered day with its title, parts, default input and parameters
+    List,
+    /// Run both parts of every registered day and print a summary table
+    All,
+    /// Check that every registered day's default input can be read
+    Check,
+    /// Run every implemented part and compare its answer against an answers file
+    Verify {
+        /// Answers file with one "day part answer" line per expected answer
+        #[arg(short, long, default_value = "resources/answers.txt")]
+        answers: String,
+
+        /// Day to verify; every registered day is verified when omitted
+        #[arg(value_parser = parse_day)]
+        day: Option<usize>,
+
+        /// Input file to use instead of the day's default input, or - for stdin
+        #[arg(short, long, requires = "day")]
+        input: Option<String>,
+
+        /// Override a challenge parameter, e.g. --param target=1500
+        #[arg(short, long = "param", value_parser = parse_param, requires = "day")]
+        params: Vec<(String, String)>,
+    },
+    /// Run every implementation of a day's parts on the same input and report disagreements
+    Diff {
+        /// Day to compare
+        #[arg(value_parser = parse_day)]
+        day: usize,
+
+        /// Part to compare; both parts are compared when omitted
+        #[arg(value_parser = parse_part)]
+        part: Option<usize>,
 
-fn main() -> Result<(), InputError> {
-    let args: Vec<String> = std::env::args().collect();
-    let day = args[1].as_str();
-    let part = args[2].as_str().parse::<usize>().map_err(InputError::Parse)?;
+        /// Input file to use instead of the day's default input, or - for stdin
+        #[arg(short, long, conflicts_with = "seeds")]
+        input: Option<String>,
 
-    let result = (adventofcode2020::challenge_by_day(day)?)().run_fn(part, &args[3..])?;
-    adventofcode2020::print_hash_map(result);
+        /// Override a challenge parameter, e.g. --param target=1500
+        #[arg(short, long = "param", value_parser = parse_param)]
+        params: Vec<(String, String)>,
+
+        /// Compare on this many generated inputs, seeded 0, 1, 2 and so on
+        #[arg(long)]
+        seeds: Option<u64>,
+
+        /// Number of records in each generated input; defaults to the real puzzle's size
+        #[arg(short = 'n', long, requires = "seeds")]
+        size: Option<usize>,
+    },
+    /// Print a random puzzle input for a day, reproducible from its seed
+    Generate {
+        /// Day to generate an input for
+        #[arg(value_parser = parse_day)]
+        day: usize,
+
+        /// Seed for the random generator
+        #[arg(short, long, default_value_t = 2020)]
+        seed: u64,
+
+        /// Number of records, such as lines or passports; defaults to the real puzzle's size
+        #[arg(short = 'n', long)]
+        size: Option<usize>,
+    },
+    /// Time a day, or one part of it, over repeated runs
+    Bench {
+        /// Day to benchmark; every registered day is benchmarked when omitted
+        #[arg(value_parser = parse_day)]
+        day: Option<usize>,
+
+        /// Part to benchmark; both parts are benchmarked when omitted
+        #[arg(value_parser = parse_part)]
+        part: Option<usize>,
+
+        /// Number of timed runs
+        #[arg(short = 'n', long, default_value_t = 10)]
+        iterations: usize,
+
+        /// Number of untimed runs before timing starts
+        #[arg(short, long, default_value_t = 2)]
+        warmup: usize,
+
+        /// Input file to use instead of the day's default input
+        #[arg(short, long, requires = "day")]
+        input: Option<String>,
+
+        /// Override a challenge parameter, e.g. --param target=1500
+        #[arg(short, long = "param", value_parser = parse_param, requires = "day")]
+        params: Vec<(String, String)>,
+
+        /// Baseline file to compare the solve medians against
+        #[arg(short, long)]
+        baseline: Option<String>,
+
+        /// Baseline file to record the solve medians into
+        #[arg(short, long)]
+        save_baseline: Option<String>,
+
+        /// Percent slowdown against the baseline that counts as a regression
+        #[arg(short, long, default_value_t = 10.0)]
+        threshold: f64,
+    },
+}
+
+fn main() -> std::process::ExitCode {
+    let cli = Cli::parse();
+    let mut out = std::io::stdout().lock();
+
+    let outcome = match cli.command {
+        Command::Run { day, part, input, params } => run(&mut out, day, part, input, params, cli.format),
+        Command::List => list(&mut out),
+        Command::All => all(&mut out, cli.format),
+        Command::Check => check(&mut out),
+        Command::Verify { answers, day, input, params } => verify(&mut out, &answers, day, input, params),
+        Command::Generate { day, seed, size } => generate(&mut out, day, seed, size),
+        Command::Diff { day, part, input, params, seeds, size } => diff(&mut out, day, part, input, params, seeds, size),
+        Command::Bench { day, part, iterations, warmup, input, params, baseline, save_baseline, threshold } =>
+            bench(&mut out, day, part, iterations, warmup, input, params, baseline, save_baseline, threshold),
+    };
+    let outcome = outcome.and_then(|()| out.flush().map_err(write_error));
+
+    return match outcome {
+        Ok(()) => std::process::ExitCode::SUCCESS,
+        // A reader that stops early, such as `head`, closes the pipe; that ends the output rather than failing.
+        Err(e) if is_broken_pipe(&e) => std::process::ExitCode::SUCCESS,
+        Err(e) => {
+            eprint!("{}", diagnostic::render(&e));
+            std::process::ExitCode::FAILURE
+        },
+    };
+}
+
+fn run(out: &mut dyn Write, day: usize, part: Option<usize>, input: Option<String>, params: Vec<(String, String)>, format: OutputFormat) -> Result<(), InputError> {
+    let args: Vec<String> = input.into_iter().collect();
+
+    let parts = match part {
+        Some(p) => vec!(p),
+        None => vec!(1, 2),
+    };
+    let runs = runner::run_day(day, &parts, &args, &params);
+
+    let mut rows = Vec::new();
+    let mut timings = Vec::new();
+    for run in runs {
+        rows.extend(output::answer_rows(run.day, run.part, &run.result?));
+        if timings.is_empty() {
+            timings.push(format!("  parse: {:.3?}", run.parse_elapsed));
+        }
+        timings.push(format!("  part {}: {:.3?}", run.part, run.solve_elapsed));
+    }
+
+    write!(out, "{}", output::render(&rows, format)).map_err(write_error)?;
+    if format == OutputFormat::Text {
+        writeln!(out, "Timing:\n{}", timings.join("\n")).map_err(write_error)?;
+    }
 
     return Ok(());
 }
+
+fn list(out: &mut dyn Write) -> Result<(), InputError> {
+    return list_entries(out).map_err(write_error);
+}
+
+fn list_entries(out: &mut dyn Write) -> std::io::Result<()> {
+    for entry in Registry::new().entries() {
+        let metadata = &entry.metadata;
+        let parts: Vec<String> = metadata.parts.iter().map(|p| p.to_string()).collect();
+        let challenge = entry.create();
+        let params: Vec<String> = challenge.get_params().iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+
+        writeln!(out, "Day {}: {} ({})", entry.day, metadata.title, metadata.puzzle_name)?;
+        writeln!(out, "  parts:  {}", parts.join(", "))?;
+        writeln!(out, "  input:  {}", metadata.default_filepath)?;
+        writeln!(out, "          {}", metadata.input_description)?;
+        if !params.is_empty() {
+            writeln!(out, "  params: {}", params.join(" "))?;
+        }
+        for part in metadata.parts {
+            let implementations = challenge.implementation_names(*part);
+            if implementations.len() > 1 {
+                writeln!(out, "  part {} implementations: {}", part, implementations.join(", "))?;
+            }
+        }
+    }
+
+    return Ok(());
+}
+
+fn all(out: &mut dyn Write, format: OutputFormat) -> Result<(), InputError> {
+    let runs = runner::run_all_days();
+
+    if format == OutputFormat::Text {
+        runner::print_summary_table(out, &runs).map_err(write_error)?;
+    } else {
+        let mut rows = Vec::new();
+        for run in &runs {
+            match &run.result {
+                Ok(answers) => rows.extend(output::answer_rows(run.day, run.part, answers)),
+                Err(e) => eprintln!("day {} part {} failed: {}", run.day, run.part, runner::error_message(e)),
+            }
+        }
+        write!(out, "{}", output::render(&rows, format)).map_err(write_error)?;
+    }
+
+    let failed_parts = runs.iter().filter(|r| !r.passed()).count();
+    if failed_parts > 0 {
+        return Err(InputError::new(ErrorKind::Failed, format!("{} part(s) failed", failed_parts)));
+    }
+
+    return Ok(());
+}
+
+fn check(out: &mut dyn Write) -> Result<(), InputError> {
+    let mut unreadable_days = 0;
+    for day in 1..=adventofcode2020::challenge_count() {
+        let challenge = challenge(day)?;
+        let filepath = challenge.get_default_filepath();
+
+        match adventofcode2020::file_lines_to_string_vec(filepath) {
+            Ok(lines) => writeln!(out, "{:>2}  ok     {} ({} lines)", day, filepath, lines.len()).map_err(write_error)?,
+            Err(e) => {
+                writeln!(out, "{:>2}  error  {} ({})", day, filepath, runner::error_message(&e)).map_err(write_error)?;
+                unreadable_days += 1;
+            },
+        }
+    }
+
+    if unreadable_days > 0 {
+        return Err(InputError::new(ErrorKind::Failed, format!("{} day(s) have unreadable inputs", unreadable_days)));
+    }
+
+    return Ok(());
+}
+
+fn verify(out: &mut dyn Write, answers_filepath: &str, day: Option<usize>, input: Option<String>, params: Vec<(String, String)>) -> Result<(), InputError> {
+    let ledger = ledger::AnswerLedger::load(answers_filepath)?;
+    let verifications = match day {
+        Some(d) => ledger::verify_day(&ledger, d, &input.into_iter().collect::<Vec<String>>(), &params)?,
+        None => ledger::verify_all(&ledger),
+    };
+    ledger::print_verifications(out, &verifications).map_err(write_error)?;
+
+    let count = |f: fn(&ledger::Verdict) -> bool| verifications.iter().filter(|v| f(&v.verdict)).count();
+    let matched = count(|v| matches!(v, ledger::Verdict::Match));
+    let mismatched = count(|v| matches!(v, ledger::Verdict::Mismatch { .. }));
+    let missing = count(|v| matches!(v, ledger::Verdict::Missing));
+    let failed = count(|v| matches!(v, ledger::Verdict::Failed(_)));
+    writeln!(out, "{} matched, {} mismatched, {} missing, {} failed", matched, mismatched, missing, failed).map_err(write_error)?;
+
+    if mismatched + failed > 0 {
+        return Err(InputError::new(ErrorKind::Failed, format!("{} part(s) did not match {}", mismatched + failed, answers_filepath)));
+    }
+
+    return Ok(());
+}
+
+fn diff(out: &mut dyn Write, day: usize, part: Option<usize>, input: Option<String>, params: Vec<(String, String)>, seeds: Option<u64>, size: Option<usize>) -> Result<(), InputError> {
+    let parts = match part {
+        Some(p) => vec!(p),
+        None => vec!(1, 2),
+    };
+
+    let comparisons = match seeds {
+        Some(count) => {
+            let size = match size {
+                Some(s) => s,
+                None => generator::puzzle_size(day)?,
+            };
+            differential::compare_generated(day, &parts, &params, 0..count, size)?
+        },
+        None => {
+            let filepath = match input {
+                Some(path) => path,
+                None => String::from(challenge(day)?.get_default_filepath()),
+            };
+            let raw_input = adventofcode2020::PuzzleInput::from_file(&filepath)?;
+            vec!((0, differential::compare_input(day, &parts, &params, &raw_input).map_err(|e| e.in_file(&filepath))?))
+        },
+    };
+
+    let mut disagreements = 0;
+    for (seed, parts) in &comparisons {
+        for comparison in parts {
+            if seeds.is_some() && comparison.agrees() {
+                continue;
+            }
+            if seeds.is_some() {
+                writeln!(out, "Seed {}:", seed).map_err(write_error)?;
+            }
+            differential::print_comparison(out, comparison).map_err(write_error)?;
+            if !comparison.agrees() {
+                disagreements += 1;
+            }
+        }
+    }
+    if let Some(count) = seeds {
+        writeln!(out, "{} disagreement(s) across {} generated input(s)", disagreements, count).map_err(write_error)?;
+    }
+
+    if disagreements > 0 {
+        return Err(InputError::new(ErrorKind::Failed, format!("{} part(s) had implementations that disagree", disagreements)));
+    }
+
+    return Ok(());
+}
+
+// Parameters the generated input needs are printed to stderr, so stdout can be redirected to a file.
+fn generate(out: &mut dyn Write, day: usize, seed: u64, size: Option<usize>) -> Result<(), InputError> {
+    let size = match size {
+        Some(s) => s,
+        None => generator::puzzle_size(day)?,
+    };
+    let generated = generator::generate(day, seed, size)?;
+
+    if !generated.params.is_empty() {
+        let params: Vec<String> = generated.params.iter().map(|(name, value)| format!("--param {}={}", name, value)).collect();
+        eprintln!("solve with: {}", params.join(" "));
+    }
+
+    return write!(out, "{}", generated.text).map_err(write_error);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn bench(
+    out: &mut dyn Write,
+    day: Option<usize>,
+    part: Option<usize>,
+    iterations: usize,
+    warmup: usize,
+    input: Option<String>,
+    params: Vec<(String, String)>,
+    baseline_filepath: Option<String>,
+    save_baseline_filepath: Option<String>,
+    threshold: f64,
+) -> Result<(), InputError> {
+    let args: Vec<String> = input.into_iter().collect();
+
+    let days = match day {
+        Some(d) => vec!(d),
+        None => (1..=adventofcode2020::challenge_count()).collect(),
+    };
+    let parts = match part {
+        Some(p) => vec!(p),
+        None => vec!(1, 2),
+    };
+
+    let baseline = match &baseline_filepath {
+        Some(path) => Some(bench::Baseline::load(path)?),
+        None => None,
+    };
+    let mut saved_baseline = match &save_baseline_filepath {
+        Some(path) => Some(bench::Baseline::load(path)?),
+        None => None,
+    };
+
+    let mut failed_parts = 0;
+    let mut regressions = 0;
+    for d in &days {
+        for p in &parts {
+            let result = match bench::bench_part(*d, *p, iterations, warmup, &args, &params) {
+                Ok(result) => result,
+                Err(e) => {
+                    writeln!(out, "Day {} part {} failed: {}", d, p, runner::error_message(&e)).map_err(write_error)?;
+                    failed_parts += 1;
+                    continue;
+                },
+            };
+            bench::print_part_bench(out, &result).map_err(write_error)?;
+
+            if let Some(b) = &baseline {
+                let comparison = bench::compare_to_baseline(&result, b, threshold);
+                bench::print_comparison(out, &comparison).map_err(write_error)?;
+                if comparison.regressed {
+                    regressions += 1;
+                }
+            }
+
+            if let Some(b) = &mut saved_baseline {
+                b.record(&result);
+            }
+        }
+    }
+
+    if let (Some(b), Some(path)) = (&saved_baseline, &save_baseline_filepath) {
+        b.save(path)?;
+        writeln!(out, "Saved baseline to {}", path).map_err(write_error)?;
+    }
+
+    if failed_parts > 0 {
+        return Err(InputError::new(ErrorKind::Failed, format!("{} part(s) failed", failed_parts)));
+    }
+    if regressions > 0 {
+        return Err(InputError::new(ErrorKind::Failed, format!("{} part(s) regressed by more than {}%", regressions, threshold)));
+    }
+
+    return Ok(());
+}
+
+fn write_error(error: std::io::Error) -> InputError {
+    return InputError::new(ErrorKind::Io, "could not write output").with_source(error);
+}
+
+fn is_broken_pipe(error: &InputError) -> bool {
+    return std::error::Error::source(error)
+        .and_then(|source| source.downcast_ref::<std::io::Error>())
+        .is_some_and(|source| source.kind() == std::io::ErrorKind::BrokenPipe);
+}
+
+fn challenge(day: usize) -> Result<Box<dyn DynChallenge>, InputError> {
+    return Ok((adventofcode2020::challenge_by_day(&day.to_string())?)());
+}
+
+fn parse_day(value: &str) -> Result<usize, String> {
+    let day_count = adventofcode2020::challenge_count();
+
+    return match value.parse::<usize>() {
+        Ok(d) if d >= 1 && d <= day_count => Ok(d),
+        _ => Err(format!("expected a day between 1 and {}", day_count)),
+    };
+}
+
+fn parse_param(value: &str) -> Result<(String, String), String> {
+    return match value.split_once('=') {
+        Some((name, param_value)) if !name.is_empty() => Ok((name.to_string(), param_value.to_string())),
+        _ => Err(String::from("expected name=value")),
+    };
+}
+
+fn parse_part(value: &str) -> Result<usize, String> {
+    return match value.parse::<usize>() {
+        Ok(p) if p == 1 || p == 2 => Ok(p),
+        _ => Err(String::from("expected part 1 or 2")),
+    };
+}
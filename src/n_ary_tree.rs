@@ -30,7 +30,7 @@ impl<T> Tree<T> {
         };
     }
 
-    pub fn iter(&self) -> PreorderIter<T> {
+    pub fn iter(&self) -> PreorderIter<'_, T> {
         return PreorderIter::new(self.root.as_ref());
     }
 }
@@ -0,0 +1,202 @@
+use crate::Answer;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Csv,
+    Tsv,
+    Value,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<OutputFormat, String> {
+        return match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            "tsv" => Ok(OutputFormat::Tsv),
+            "value" => Ok(OutputFormat::Value),
+            _ => Err(String::from("expected one of text, json, csv, tsv, value")),
+        };
+    }
+}
+
+pub struct AnswerRow {
+    pub day: usize,
+    pub part: usize,
+    pub key: String,
+    pub value: Answer,
+}
+
+pub fn answer_rows(day: usize, part: usize, result: &std::collections::HashMap<String, Answer>) -> Vec<AnswerRow> {
+    let mut rows: Vec<AnswerRow> = result.iter()
+        .map(|(key, value)| AnswerRow {
+            day: day,
+            part: part,
+            key: key.clone(),
+            value: value.clone(),
+        })
+        .collect();
+    rows.sort_by(|a, b| a.key.cmp(&b.key));
+
+    return rows;
+}
+
+pub fn render(rows: &[AnswerRow], format: OutputFormat) -> String {
+    return match format {
+        OutputFormat::Text => render_text(rows),
+        OutputFormat::Json => render_json(rows),
+        OutputFormat::Csv => render_delimited(rows, ','),
+        OutputFormat::Tsv => render_delimited(rows, '\t'),
+        OutputFormat::Value => rows.iter().map(|r| format!("{}\n", r.value)).collect(),
+    };
+}
+
+fn render_text(rows: &[AnswerRow]) -> String {
+    let mut text = String::new();
+    let mut current_part = None;
+    for row in rows {
+        if current_part != Some((row.day, row.part)) {
+            text.push_str(&format!("Day {} part {}\nResult:\n", row.day, row.part));
+            current_part = Some((row.day, row.part));
+        }
+
+        text.push_str(&format!("  {}: {}\n", row.key, row.value));
+    }
+
+    return text;
+}
+
+fn render_json(rows: &[AnswerRow]) -> String {
+    let objects: Vec<String> = rows.iter()
+        .map(|r| format!(
+            "  {{\"day\": {}, \"part\": {}, \"key\": {}, \"value\": {}}}",
+            r.day,
+            r.part,
+            json_string(&r.key),
+            json_value(&r.value),
+        ))
+        .collect();
+
+    if objects.is_empty() {
+        return String::from("[]\n");
+    }
+
+    return format!("[\n{}\n]\n", objects.join(",\n"));
+}
+
+fn render_delimited(rows: &[AnswerRow], delimiter: char) -> String {
+    let mut text = ["day", "part", "key", "value"].join(&delimiter.to_string());
+    text.push('\n');
+
+    for row in rows {
+        let fields = [
+            row.day.to_string(),
+            row.part.to_string(),
+            delimited_field(&row.key, delimiter),
+            delimited_field(&row.value.to_string(), delimiter),
+        ];
+        text.push_str(&fields.join(&delimiter.to_string()));
+        text.push('\n');
+    }
+
+    return text;
+}
+
+// Numbers are written as JSON numbers at full width, so consumers should parse big values as such.
+fn json_value(value: &Answer) -> String {
+    return match value {
+        Answer::Text(s) => json_string(s),
+        _ => value.to_string(),
+    };
+}
+
+fn json_string(s: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+
+    return escaped;
+}
+
+// CSV fields are quoted when needed; TSV has no quoting, so tabs and newlines are escaped instead.
+fn delimited_field(field: &str, delimiter: char) -> String {
+    if delimiter == '\t' {
+        return field.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n");
+    }
+
+    if field.contains(delimiter) || field.contains('"') || field.contains('\n') {
+        return format!("\"{}\"", field.replace('"', "\"\""));
+    }
+
+    return field.to_string();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Answer;
+    use super::OutputFormat;
+
+    fn rows() -> Vec<super::AnswerRow> {
+        let result: std::collections::HashMap<String, Answer> = [
+            (String::from("zeta"), Answer::Text(String::from("b,c"))),
+            (String::from("alpha, \"quoted\""), Answer::Signed(-1)),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+
+        return super::answer_rows(3, 2, &result);
+    }
+
+    #[test]
+    fn render_text() {
+        let expected = "Day 3 part 2\nResult:\n  alpha, \"quoted\": -1\n  zeta: b,c\n";
+
+        assert_eq!(expected, super::render(&rows(), OutputFormat::Text));
+    }
+
+    #[test]
+    fn render_json() {
+        let expected = concat!(
+            "[\n",
+            "  {\"day\": 3, \"part\": 2, \"key\": \"alpha, \\\"quoted\\\"\", \"value\": -1},\n",
+            "  {\"day\": 3, \"part\": 2, \"key\": \"zeta\", \"value\": \"b,c\"}\n",
+            "]\n",
+        );
+
+        assert_eq!(expected, super::render(&rows(), OutputFormat::Json));
+    }
+
+    #[test]
+    fn render_csv() {
+        let expected = "day,part,key,value\n3,2,\"alpha, \"\"quoted\"\"\",-1\n3,2,zeta,\"b,c\"\n";
+
+        assert_eq!(expected, super::render(&rows(), OutputFormat::Csv));
+    }
+
+    #[test]
+    fn render_tsv() {
+        let expected = "day\tpart\tkey\tvalue\n3\t2\talpha, \"quoted\"\t-1\n3\t2\tzeta\tb,c\n";
+
+        assert_eq!(expected, super::render(&rows(), OutputFormat::Tsv));
+    }
+
+    #[test]
+    fn render_value() {
+        assert_eq!("-1\nb,c\n", super::render(&rows(), OutputFormat::Value));
+    }
+}
@@ -1,27 +1,35 @@
+use crate::input_error::ErrorKind;
 use crate::input_error::InputError;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Passport {
+    #[cfg_attr(feature = "serde", serde(rename = "fields"))]
     value_map: std::collections::HashMap<String, String>,
 }
 
 impl Passport {
     pub fn new(key_value_row: &str) -> Result<Passport, InputError> {
         let mut value_map = std::collections::HashMap::<String, String>::new();
-        let key_value_pairs = key_value_row
-            .split(" ")
-            .map(|p| p.split(":"));
+        let mut column = 1;
+
+        for raw_pair in key_value_row.split(" ") {
+            let mut pair = raw_pair.split(":");
+            let bad_pair = || InputError::new(ErrorKind::MalformedPassport, "expected a key:value pair")
+                .with_text(raw_pair)
+                .with_line_text(key_value_row)
+                .at_column(column);
 
-        for mut pair in key_value_pairs {
             let key = match pair.next() {
                 Some(k) => k,
-                None => return Err(InputError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, "Bad key:value pair"))),
+                None => return Err(bad_pair()),
             };
             let value = match pair.next() {
                 Some(v) => v,
-                None => return Err(InputError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, "Bad key:value pair"))),
+                None => return Err(bad_pair()),
             };
 
             value_map.insert(String::from(key), String::from(value));
+            column += raw_pair.chars().count() + 1;
         }
 
         return Ok(Passport { value_map: value_map });
@@ -73,7 +81,7 @@ impl Passport {
         //byr (Birth Year) - four digits; at least 1920 and at most 2002.
 
         return match year.parse::<usize>() {
-            Ok(y) => y >= 1920 && y <= 2002,
+            Ok(y) => (1920..=2002).contains(&y),
             Err(_) => false,
         }
     }
@@ -82,7 +90,7 @@ impl Passport {
         //iyr (Issue Year) - four digits; at least 2010 and at most 2020.
 
         return match year.parse::<usize>() {
-            Ok(y) => y >= 2010 && y <= 2020,
+            Ok(y) => (2010..=2020).contains(&y),
             Err(_) => false,
         }
     }
@@ -90,8 +98,8 @@ impl Passport {
     fn expiration_year_valid(year: &String) -> bool {
         //eyr (Expiration Year) - four digits; at least 2020 and at most 2030.
 
-        return match year.parse::<usize>().map_err(InputError::Parse) {
-            Ok(y) => y >= 2020 && y <= 2030,
+        return match year.parse::<usize>() {
+            Ok(y) => (2020..=2030).contains(&y),
             Err(_) => false,
         }
     }
@@ -101,7 +109,13 @@ impl Passport {
         //If cm, the number must be at least 150 and at most 193.
         //If in, the number must be at least 59 and at most 76.
 
-        let height_parts = height.split_at(height.len() - 2);
+        let height_parts = match height.strip_suffix("cm") {
+            Some(h) => (h, "cm"),
+            None => match height.strip_suffix("in") {
+                Some(h) => (h, "in"),
+                None => return false,
+            },
+        };
 
         let h = match height_parts.0.parse::<usize>() {
             Ok(h) => h,
@@ -109,8 +123,8 @@ impl Passport {
         };
 
         return match height_parts.1 {
-            "cm" => h >= 150 && h <= 193,
-            "in" => h >= 59 && h <= 76,
+            "cm" => (150..=193).contains(&h),
+            "in" => (59..=76).contains(&h),
             _ => false,
         };
     }
@@ -119,7 +133,7 @@ impl Passport {
         //hcl (Hair Color) - a # followed by exactly six characters 0-9 or a-f.
 
         return match regex::Regex::new(r"^#[0-9a-f]{6}$") {
-            Ok(r) => r.is_match(&hair_color),
+            Ok(r) => r.is_match(hair_color),
             Err(_) => false,
         };
     }
@@ -127,15 +141,15 @@ impl Passport {
     fn eye_color_valid(eye_color: &String) -> bool {
         //ecl (Eye Color) - exactly one of: amb blu brn gry grn hzl oth.
 
-        return vec!("amb", "blu", "brn", "gry", "grn", "hzl", "oth").iter()
-            .any(|c| c == &eye_color);
+        return ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"].iter()
+            .any(|c| c == eye_color);
     }
 
     fn passport_id_valid(passport_id: &String) -> bool {
         //pid (Passport ID) - a nine-digit number, including leading zeroes.
 
         return match regex::Regex::new(r"^[0-9]{9}$") {
-            Ok(r) => r.is_match(&passport_id),
+            Ok(r) => r.is_match(passport_id),
             Err(_) => false,
         };
     }
@@ -143,12 +157,22 @@ impl Passport {
     //cid (Country ID) - ignored, missing or not.
 }
 
+// Parses a single passport whose fields are all on one line.
+impl std::str::FromStr for Passport {
+    type Err = InputError;
+
+    fn from_str(key_value_row: &str) -> Result<Passport, InputError> {
+        return Passport::new(key_value_row);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::InputError;
     use super::Passport;
 
     #[test]
+    #[allow(clippy::needless_range_loop, clippy::comparison_to_empty, clippy::needless_borrow, clippy::single_char_add_str)]
     fn count_valid_passports_with_validation() -> Result<(), InputError> {
         let values = vec!(
             String::from("ecl:gry pid:860033327 eyr:2020 hcl:#fffffd"),
@@ -192,6 +216,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::needless_range_loop, clippy::comparison_to_empty, clippy::needless_borrow, clippy::single_char_add_str)]
     fn count_invalid_passports_with_validators() -> Result<(), InputError> {
         let values = vec!(
             String::from("eyr:1972 cid:100"),
@@ -235,6 +260,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::needless_range_loop, clippy::comparison_to_empty, clippy::needless_borrow, clippy::single_char_add_str)]
     fn count_valid_passports_with_validators() -> Result<(), InputError> {
         let values = vec!(
             String::from("pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980"),
@@ -275,4 +301,22 @@ mod tests {
 
         return Ok(());
     }
+
+    #[test]
+    fn parse_passport() {
+        let passport: Passport = "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd byr:1937 iyr:2017 cid:147 hgt:183cm".parse().unwrap();
+
+        assert!(passport.is_valid(true));
+        assert_eq!(Some(9), "ecl:gry pid".parse::<Passport>().err().and_then(|e| e.column()));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let passport: Passport = "ecl:gry".parse().unwrap();
+
+        let json = serde_json::to_string(&passport).unwrap();
+        assert_eq!(r#"{"fields":{"ecl":"gry"}}"#, json);
+        assert_eq!(passport.value_map, serde_json::from_str::<Passport>(&json).unwrap().value_map);
+    }
 }
@@ -1,39 +1,125 @@
+use crate::input_error::ErrorKind;
 use crate::input_error::InputError;
+use crate::input::PuzzleInput;
 
+// A password policy and the password it applies to. The two policy numbers are the minimum and
+// maximum character count, or the two 1-based character positions, depending on the rule applied.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "EntryFields", try_from = "EntryFields"))]
 pub struct PasswordDatabaseEntry {
-    is_valid_by_count: bool,
-    is_valid_by_position: bool,
+    first: usize,
+    second: usize,
+    character: char,
+    password: String,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct EntryFields {
+    first: usize,
+    second: usize,
+    character: char,
+    password: String,
+}
+
+#[cfg(feature = "serde")]
+impl From<PasswordDatabaseEntry> for EntryFields {
+    fn from(entry: PasswordDatabaseEntry) -> EntryFields {
+        return EntryFields { first: entry.first, second: entry.second, character: entry.character, password: entry.password };
+    }
+}
+
+#[cfg(feature = "serde")]
+impl std::convert::TryFrom<EntryFields> for PasswordDatabaseEntry {
+    type Error = InputError;
+
+    fn try_from(fields: EntryFields) -> Result<PasswordDatabaseEntry, InputError> {
+        return PasswordDatabaseEntry::new(fields.first, fields.second, fields.character, &fields.password);
+    }
 }
 
 impl PasswordDatabaseEntry {
     pub fn new(condition1: usize, condition2: usize, required_character: char, password: &str) -> Result<PasswordDatabaseEntry, InputError> {
+        if condition1 == 0 || condition2 == 0 {
+            return Err(InputError::new(ErrorKind::MalformedPassword, "character positions start at 1"));
+        }
+        if condition1 > condition2 {
+            return Err(InputError::new(ErrorKind::MalformedPassword, "the first number must not exceed the second"));
+        }
+
         return Ok(
             PasswordDatabaseEntry {
-                is_valid_by_count: PasswordDatabaseEntry::password_valid_by_character_count(condition1, condition2, &required_character, &password),
-                is_valid_by_position: PasswordDatabaseEntry::password_valid_by_character_position(condition1 - 1, condition2 - 1, &required_character, &password)?,
+                first: condition1,
+                second: condition2,
+                character: required_character,
+                password: password.to_string(),
             }
         );
     }
 
-    fn password_valid_by_character_count(min_count: usize, max_count: usize, required_character: &char, password: &str) -> bool {
-        let actual_character_count = password.matches(&required_character.to_string()).count();
-        return actual_character_count >= min_count && actual_character_count <= max_count;
+    pub fn is_valid_by_count(&self) -> bool {
+        let actual_character_count = self.password.chars().filter(|c| *c == self.character).count();
+        return actual_character_count >= self.first && actual_character_count <= self.second;
     }
 
-    fn password_valid_by_character_position(position1: usize, position2: usize, required_character: &char, password: &str) -> Result<bool, InputError> {
-        let found_in_position1 = match password.chars().nth(position1) {
-            Some(c) => c == *required_character,
-            None => return Err(InputError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, "Bad character conditions"))),
+    // Positions outside the password never match.
+    pub fn is_valid_by_position(&self) -> bool {
+        let found_at = |position: usize| position.checked_sub(1)
+            .and_then(|i| self.password.chars().nth(i))
+            == Some(self.character);
+
+        return found_at(self.first) ^ found_at(self.second);
+    }
+}
+
+impl std::str::FromStr for PasswordDatabaseEntry {
+    type Err = InputError;
+
+    // Entries look like "1-3 a: abcde"; columns count characters from the start of the entry.
+    fn from_str(raw_entry: &str) -> Result<PasswordDatabaseEntry, InputError> {
+        let mut elements = raw_entry.split(" ");
+
+        let conditions_blob = match elements.next() {
+            Some(r) => r,
+            None => return Err(malformed("", 1, "expected a position range like 1-3")),
+        };
+        let conditions: Vec<&str> = conditions_blob.split("-").collect();
+        if conditions.len() != 2 {
+            return Err(malformed(conditions_blob, 1, "expected a position range like 1-3"));
+        }
+        let condition1 = conditions[0].parse::<usize>()
+            .map_err(|e| malformed(conditions[0], 1, "expected a number").with_source(e))?;
+        let condition2 = conditions[1].parse::<usize>()
+            .map_err(|e| malformed(conditions[1], conditions[0].chars().count() + 2, "expected a number").with_source(e))?;
+
+        let required_character_column = conditions_blob.chars().count() + 2;
+        let required_character_blob = match elements.next() {
+            Some(c) => c,
+            None => return Err(malformed("", required_character_column, "expected a required character followed by a colon")),
         };
-        let found_in_position2 = match password.chars().nth(position2) {
-            Some(c) => c == *required_character,
-            None => return Err(InputError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, "Bad character conditions"))),
+        let required_character_chars: Vec<char> = required_character_blob.chars().collect();
+        if required_character_chars.len() != 2 || required_character_chars[1] != ':' {
+            return Err(malformed(required_character_blob, required_character_column, "expected a required character followed by a colon"));
+        }
+        let required_character = required_character_chars[0];
+
+        let password_column = required_character_column + required_character_blob.chars().count() + 1;
+        let password = match elements.next() {
+            Some(p) => p,
+            None => return Err(malformed("", password_column, "expected a password")),
         };
 
-        return Ok(found_in_position1 ^ found_in_position2);
+        return PasswordDatabaseEntry::new(condition1, condition2, required_character, password)
+            .map_err(|e| e.with_text(conditions_blob).at_column(1));
     }
 }
 
+fn malformed(text: &str, column: usize, message: &str) -> InputError {
+    return InputError::new(ErrorKind::MalformedPassword, message).with_text(text).at_column(column);
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PasswordDatabase {
     entries: Vec<PasswordDatabaseEntry>,
 }
@@ -41,31 +127,8 @@ pub struct PasswordDatabase {
 impl PasswordDatabase {
     pub fn new(raw_entries: &Vec<String>) -> Result<PasswordDatabase, InputError> {
         let mut entries:Vec<PasswordDatabaseEntry> = Vec::new();
-        for raw_entry in raw_entries {
-            let mut elements = raw_entry.split(" ");
-
-            let conditions:Vec<&str> = match elements.next() {
-                Some(r) => r.split("-").collect(),
-                None => return Err(InputError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, "Bad character conditions"))),
-            };
-            let condition1 = conditions[0].parse::<usize>().map_err(InputError::Parse)?;
-            let condition2 = conditions[1].parse::<usize>().map_err(InputError::Parse)?;
-
-            let required_character_blob = match elements.next() {
-                Some(c) => c.chars().next(),
-                None => return Err(InputError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, "Bad required character"))),
-            };
-            let required_character = match required_character_blob {
-                Some(c) => c,
-                None => return Err(InputError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, "Bad required character"))),
-            };
-
-            let password = match elements.next() {
-                Some(p) => p,
-                None => return Err(InputError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, "Bad password"))),
-            };
-
-            entries.push(PasswordDatabaseEntry::new(condition1, condition2, required_character, password)?);
+        for (i, raw_entry) in raw_entries.iter().enumerate() {
+            entries.push(raw_entry.parse::<PasswordDatabaseEntry>().map_err(|e| e.with_line_text(raw_entry).at_line(i + 1))?);
         }
 
         return Ok(
@@ -75,12 +138,20 @@ impl PasswordDatabase {
         );
     }
 
-    pub fn valid_passwords_by_character_count(&mut self) -> usize {
-        return (&self.entries).into_iter().filter(|e| e.is_valid_by_count).count();
+    pub fn valid_passwords_by_character_count(&self) -> usize {
+        return self.entries.iter().filter(|e| e.is_valid_by_count()).count();
     }
 
-    pub fn valid_passwords_by_character_position(&mut self) -> usize {
-        return (&self.entries).into_iter().filter(|e| e.is_valid_by_position).count();
+    pub fn valid_passwords_by_character_position(&self) -> usize {
+        return self.entries.iter().filter(|e| e.is_valid_by_position()).count();
+    }
+}
+
+impl std::convert::TryFrom<&str> for PasswordDatabase {
+    type Error = InputError;
+
+    fn try_from(text: &str) -> Result<PasswordDatabase, InputError> {
+        return PasswordDatabase::new(&PuzzleInput::from_text(text).content_lines().to_vec());
     }
 }
 
@@ -88,6 +159,8 @@ impl PasswordDatabase {
 mod tests {
     use super::InputError;
     use super::PasswordDatabase;
+    use super::PasswordDatabaseEntry;
+    use std::convert::TryFrom;
 
     #[test]
     fn valid_passwords_by_count() -> Result<(), InputError> {
@@ -96,7 +169,7 @@ mod tests {
             String::from("1-3 b: cdefg"),
             String::from("2-9 c: ccccccccc"),
         );
-        let mut password_database = PasswordDatabase::new(&values)?;
+        let password_database = PasswordDatabase::new(&values)?;
 
         let actual = password_database.valid_passwords_by_character_count();
 
@@ -112,7 +185,7 @@ mod tests {
             String::from("1-3 b: cdefg"),
             String::from("2-9 c: ccccccccc"),
         );
-        let mut password_database = PasswordDatabase::new(&values)?;
+        let password_database = PasswordDatabase::new(&values)?;
 
         let actual = password_database.valid_passwords_by_character_position();
 
@@ -120,4 +193,57 @@ mod tests {
 
         return Ok(());
     }
+
+    #[test]
+    fn malformed_entry_reports_location() {
+        let values = vec!(
+            String::from("1-3 a: abcde"),
+            String::from("1-x b: cdefg"),
+        );
+
+        let error = match PasswordDatabase::new(&values) {
+            Err(e) => e,
+            Ok(_) => panic!("expected a malformed entry"),
+        };
+
+        assert_eq!(crate::ErrorKind::MalformedPassword, error.kind());
+        assert_eq!(Some(2), error.line());
+        assert_eq!(Some(3), error.column());
+        assert_eq!(Some("x"), error.text());
+    }
+
+    #[test]
+    fn parse_entries_and_documents() {
+        let entry: PasswordDatabaseEntry = "1-3 a: abcde".parse().unwrap();
+        assert!(entry.is_valid_by_count() && entry.is_valid_by_position());
+        let entry: PasswordDatabaseEntry = "1-10 a: aaa".parse().unwrap();
+        assert!(entry.is_valid_by_count() && entry.is_valid_by_position());
+        assert_eq!(Some(5), "1-3 a cdefg".parse::<PasswordDatabaseEntry>().err().and_then(|e| e.column()));
+
+        let password_database = PasswordDatabase::try_from("1-3 a: abcde\n1-3 b: cdefg\n").unwrap();
+        assert_eq!(1, password_database.valid_passwords_by_character_count());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let password_database = PasswordDatabase::try_from("1-3 a: abcde\n").unwrap();
+
+        let json = serde_json::to_string(&password_database).unwrap();
+        assert_eq!(r#"{"entries":[{"first":1,"second":3,"character":"a","password":"abcde"}]}"#, json);
+
+        let restored: PasswordDatabase = serde_json::from_str(&json).unwrap();
+        assert_eq!(1, restored.valid_passwords_by_character_position());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_rejects_invalid_policies() {
+        for json in &[
+            r#"{"entries":[{"first":3,"second":1,"character":"a","password":"abcde"}]}"#,
+            r#"{"entries":[{"first":0,"second":3,"character":"a","password":"abcde"}]}"#,
+        ] {
+            assert!(serde_json::from_str::<PasswordDatabase>(json).is_err(), "{}", json);
+        }
+    }
 }
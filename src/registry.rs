@@ -0,0 +1,137 @@
+use crate::challenges;
+use crate::challenges::ChallengeMetadata;
+use crate::challenges::DynChallenge;
+use crate::ErrorKind;
+use crate::InputError;
+
+pub struct RegistryEntry {
+    pub day: usize,
+    pub metadata: ChallengeMetadata,
+    constructor: fn() -> Box<dyn DynChallenge>,
+}
+
+impl RegistryEntry {
+    pub fn create(&self) -> Box<dyn DynChallenge> {
+        return (self.constructor)();
+    }
+
+    pub fn constructor(&self) -> fn() -> Box<dyn DynChallenge> {
+        return self.constructor;
+    }
+}
+
+pub struct Registry {
+    entries: Vec<RegistryEntry>,
+}
+
+impl Registry {
+    pub fn new() -> Registry {
+        let constructors: [fn() -> Box<dyn DynChallenge>; 10] = [
+            challenges::day1::challenge,
+            challenges::day2::challenge,
+            challenges::day3::challenge,
+            challenges::day4::challenge,
+            challenges::day5::challenge,
+            challenges::day6::challenge,
+            challenges::day7::challenge,
+            challenges::day8::challenge,
+            challenges::day9::challenge,
+            challenges::day10::challenge,
+        ];
+
+        let entries = constructors.iter()
+            .enumerate()
+            .map(|(i, constructor)| RegistryEntry {
+                day: i + 1,
+                metadata: constructor().get_metadata(),
+                constructor: *constructor,
+            })
+            .collect();
+
+        return Registry { entries: entries };
+    }
+
+    pub fn get(&self, day: usize) -> Result<&RegistryEntry, InputError> {
+        return self.entries.iter()
+            .find(|e| e.day == day)
+            .ok_or_else(|| unknown_day(&day.to_string()));
+    }
+
+    pub fn get_by_name(&self, day: &str) -> Result<&RegistryEntry, InputError> {
+        return match day.trim().parse::<usize>() {
+            Ok(d) => self.get(d),
+            Err(_) => Err(unknown_day(day)),
+        };
+    }
+
+    pub fn entries(&self) -> &[RegistryEntry] {
+        return &self.entries;
+    }
+
+    pub fn len(&self) -> usize {
+        return self.entries.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.entries.is_empty();
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Registry {
+        return Registry::new();
+    }
+}
+
+pub(crate) fn unknown_day(day: &str) -> InputError {
+    return InputError::new(ErrorKind::UnknownDay, format!("unknown day: {}", day)).with_text(day);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Registry;
+    use crate::ErrorKind;
+
+    #[test]
+    fn unknown_days_are_errors() {
+        let registry = Registry::new();
+
+        for day in &["0", "11", "abc", "", "-1"] {
+            match registry.get_by_name(day) {
+                Err(e) => {
+                    assert_eq!(ErrorKind::UnknownDay, e.kind());
+                    assert_eq!(Some(*day), e.text());
+                },
+                _ => panic!("expected unknown day for {:?}", day),
+            }
+        }
+    }
+
+    #[test]
+    fn entries_carry_metadata() {
+        let registry = Registry::new();
+        let entry = registry.get(7).unwrap();
+
+        assert_eq!(10, registry.len());
+        assert_eq!("Handy Haversacks", entry.metadata.title);
+        assert_eq!(&[1, 2], entry.metadata.parts);
+        assert_eq!(entry.metadata.default_filepath, entry.create().get_default_filepath());
+    }
+
+    #[test]
+    fn trailing_blank_lines_are_ignored_by_every_day() {
+        for entry in Registry::new().entries() {
+            let challenge = entry.create();
+            let mut lines = crate::file_lines_to_string_vec(entry.metadata.default_filepath).unwrap();
+            let input = crate::PuzzleInput::from_lines(lines.clone());
+            lines.extend(vec!(String::from(""), String::from("  "), String::from("")));
+            let padded = crate::PuzzleInput::from_lines(lines);
+
+            let parsed = challenge.parse_input(&input).unwrap();
+            let parsed_padded = challenge.parse_input(&padded).unwrap_or_else(|e| panic!("day {}: {}", entry.day, e));
+            for part in entry.metadata.parts {
+                assert_eq!(challenge.solve(*part, &parsed).unwrap(), challenge.solve(*part, &parsed_padded).unwrap(), "day {} part {}", entry.day, part);
+            }
+        }
+    }
+}
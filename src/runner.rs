@@ -0,0 +1,197 @@
+use crate::challenges::DynChallenge;
+use crate::challenges::ParsedInput;
+use crate::ErrorKind;
+use crate::InputError;
+use crate::PuzzleInput;
+use crate::ResultHashMap;
+
+pub struct PartRun {
+    pub day: usize,
+    pub part: usize,
+    pub result: ResultHashMap,
+    pub parse_elapsed: std::time::Duration,
+    pub solve_elapsed: std::time::Duration,
+}
+
+impl PartRun {
+    pub fn passed(&self) -> bool {
+        return self.result.is_ok();
+    }
+}
+
+// The input is read and parsed once and shared by every requested part, so each run reports the
+// same parse time alongside its own solve time.
+pub fn run_day(day: usize, parts: &[usize], args: &[String], params: &[(String, String)]) -> Vec<PartRun> {
+    let parse_start = std::time::Instant::now();
+    let prepared = prepare_day(day, args, params);
+    let parse_elapsed = parse_start.elapsed();
+
+    let mut runs = Vec::new();
+    for part in parts {
+        let solve_start = std::time::Instant::now();
+        let result = match &prepared {
+            Ok((challenge, input)) => catch_panics(|| challenge.solve(*part, input)),
+            Err(e) => Err(e.clone()),
+        };
+
+        runs.push(PartRun {
+            day: day,
+            part: *part,
+            result: result,
+            parse_elapsed: parse_elapsed,
+            solve_elapsed: solve_start.elapsed(),
+        });
+    }
+
+    return runs;
+}
+
+pub fn run_all_days() -> Vec<PartRun> {
+    let mut runs = Vec::new();
+    for day in 1..=crate::challenge_count() {
+        runs.extend(run_day(day, &[1, 2], &[], &[]));
+    }
+
+    return runs;
+}
+
+fn prepare_day(day: usize, args: &[String], params: &[(String, String)]) -> Result<(Box<dyn DynChallenge>, ParsedInput), InputError> {
+    let mut challenge = (crate::challenge_by_day(&day.to_string())?)();
+    challenge.set_params(params)?;
+    let filepath = match args.first() {
+        Some(path) => path.clone(),
+        None => String::from(challenge.get_default_filepath()),
+    };
+
+    let raw_input = PuzzleInput::from_file(&filepath)?;
+    let input = catch_panics(|| challenge.parse_input(&raw_input)).map_err(|e| e.in_file(&filepath))?;
+
+    return Ok((challenge, input));
+}
+
+pub(crate) fn catch_panics<T>(f: impl FnOnce() -> Result<T, InputError>) -> Result<T, InputError> {
+    return std::panic::catch_unwind(std::panic::AssertUnwindSafe(f))
+        .unwrap_or_else(|payload| Err(panic_to_error(payload)));
+}
+
+pub fn print_summary_table(out: &mut dyn std::io::Write, runs: &[PartRun]) -> std::io::Result<()> {
+    let header = [
+        String::from("Day"),
+        String::from("Part"),
+        String::from("Answer"),
+        String::from("Value"),
+        String::from("Parse"),
+        String::from("Solve"),
+        String::from("Status"),
+    ];
+
+    let mut rows = Vec::new();
+    for run in runs {
+        let status = if run.passed() { "pass" } else { "fail" };
+        let parse_elapsed = format!("{:.3?}", run.parse_elapsed);
+        let solve_elapsed = format!("{:.3?}", run.solve_elapsed);
+
+        match &run.result {
+            Ok(answers) => {
+                let mut keys: Vec<&String> = answers.keys().collect();
+                keys.sort();
+                for key in keys {
+                    rows.push([
+                        run.day.to_string(),
+                        run.part.to_string(),
+                        key.clone(),
+                        answers[key].to_string(),
+                        parse_elapsed.clone(),
+                        solve_elapsed.clone(),
+                        String::from(status),
+                    ]);
+                }
+            },
+            Err(e) => rows.push([
+                run.day.to_string(),
+                run.part.to_string(),
+                String::from("-"),
+                error_message(e),
+                parse_elapsed.clone(),
+                solve_elapsed.clone(),
+                String::from(status),
+            ]),
+        }
+    }
+
+    let mut widths = header.iter().map(|h| h.len()).collect::<Vec<usize>>();
+    for row in &rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.len());
+        }
+    }
+
+    print_table_row(out, &header, &widths)?;
+    writeln!(out, "{}", widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<String>>().join("-+-"))?;
+    for row in &rows {
+        print_table_row(out, row, &widths)?;
+    }
+
+    return Ok(());
+}
+
+fn print_table_row(out: &mut dyn std::io::Write, cells: &[String], widths: &[usize]) -> std::io::Result<()> {
+    let padded: Vec<String> = cells.iter().zip(widths)
+        .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+        .collect();
+
+    return writeln!(out, "{}", padded.join(" | ").trim_end());
+}
+
+// One-line form of an error followed by its sources, for table cells and per-part summaries.
+pub fn error_message(error: &InputError) -> String {
+    let mut message = error.to_string();
+    let mut source = std::error::Error::source(error);
+    while let Some(s) = source {
+        message.push_str(&format!(": {}", s));
+        source = s.source();
+    }
+
+    return message;
+}
+
+fn panic_to_error(payload: Box<dyn std::any::Any + Send>) -> InputError {
+    let message = match payload.downcast_ref::<&str>() {
+        Some(s) => s.to_string(),
+        None => match payload.downcast_ref::<String>() {
+            Some(s) => s.clone(),
+            None => String::from("unknown panic"),
+        },
+    };
+
+    return InputError::new(ErrorKind::Panic, format!("solver panicked: {}", message));
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn run_day_records_answers() {
+        let mut runs = super::run_day(1, &[1, 2], &[], &[]);
+
+        assert_eq!(2, runs.len());
+        assert_eq!(runs[0].parse_elapsed, runs[1].parse_elapsed);
+        assert_eq!(crate::Answer::Unsigned(73616634), runs.pop().unwrap().result.unwrap()["expense report values product"]);
+        assert_eq!(crate::Answer::Unsigned(121396), runs.pop().unwrap().result.unwrap()["expense report values product"]);
+    }
+
+    #[test]
+    fn run_day_records_failures() {
+        let runs = super::run_day(1, &[1, 2], &[String::from("resources/does_not_exist.txt")], &[]);
+
+        assert!(runs.iter().all(|r| !r.passed()));
+    }
+
+    #[test]
+    fn run_day_applies_params() {
+        let params = [(String::from("target"), String::from("1500"))];
+        let runs = super::run_day(1, &[1], &[], &params);
+
+        let answer = runs[0].result.as_ref().ok().map(|answers| answers["expense report values product"].clone());
+        assert_ne!(Some(crate::Answer::Unsigned(121396)), answer);
+    }
+}
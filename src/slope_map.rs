@@ -1,3 +1,7 @@
+use crate::input_error::ErrorKind;
+use crate::input_error::InputError;
+use crate::input::PuzzleInput;
+
 pub struct MovementPath {
     horizontal: i64,
     downward: i64,
@@ -12,25 +16,55 @@ impl MovementPath {
     }
 }
 
+// Serialized as its rows of text, and checked like parsed input when deserialized.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "SlopeMapRows", try_from = "SlopeMapRows"))]
 pub struct SlopeMap {
     map: Vec<Vec<char>>,
 }
 
-impl SlopeMap {
-    pub fn new(map_rows: &Vec<String>) -> SlopeMap {
-        let mut map: Vec<Vec<char>> = Vec::new();
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SlopeMapRows {
+    rows: Vec<String>,
+}
 
-        for map_row in map_rows {
-            let mut row = Vec::new();
+#[cfg(feature = "serde")]
+impl From<SlopeMap> for SlopeMapRows {
+    fn from(slope_map: SlopeMap) -> SlopeMapRows {
+        return SlopeMapRows { rows: slope_map.map.iter().map(|row| row.iter().collect()).collect() };
+    }
+}
 
-            for c in map_row.chars() {
-                row.push(c);
-            }
+#[cfg(feature = "serde")]
+impl std::convert::TryFrom<SlopeMapRows> for SlopeMap {
+    type Error = InputError;
 
-            map.push(row);
+    fn try_from(rows: SlopeMapRows) -> Result<SlopeMap, InputError> {
+        return SlopeMap::new(&rows.rows);
+    }
+}
+
+impl SlopeMap {
+    // Rows repeat to the right, so the map must be a rectangular grid of '.' and '#'.
+    pub fn new(map_rows: &Vec<String>) -> Result<SlopeMap, InputError> {
+        let input = PuzzleInput::from_lines(map_rows.clone());
+        let map = input.grid()?;
+
+        for (i, row) in map.iter().enumerate() {
+            if let Some(column) = row.iter().position(|c| *c != '.' && *c != SlopeMap::tree_char()) {
+                return Err(
+                    InputError::new(ErrorKind::MalformedMap, "expected '.' or '#'")
+                        .with_text(&row[column].to_string())
+                        .with_line_text(&input.lines()[i])
+                        .at_column(column + 1)
+                        .at_line(i + 1)
+                );
+            }
         }
 
-        return SlopeMap {map: map};
+        return Ok(SlopeMap {map: map});
     }
 
     pub fn count_trees_on_traversal(&self, movement_path: &MovementPath) -> usize {
@@ -44,11 +78,13 @@ impl SlopeMap {
                 trees_hit += 1;
             }
 
-            let mut new_x = index.0 + movement_path.horizontal;
-            if new_x as usize >= current_row.len() {
-                new_x -= current_row.len() as i64;
-            }
-            index = (new_x, index.1 + movement_path.downward);
+            let width = current_row.len() as i64;
+            let new_x = (index.0 + movement_path.horizontal.rem_euclid(width)).rem_euclid(width);
+            let new_y = match index.1.checked_add(movement_path.downward) {
+                Some(y) => y,
+                None => break,
+            };
+            index = (new_x, new_y);
         }
 
         return trees_hit;
@@ -60,9 +96,18 @@ impl SlopeMap {
     }
 }
 
+impl std::convert::TryFrom<&str> for SlopeMap {
+    type Error = InputError;
+
+    fn try_from(text: &str) -> Result<SlopeMap, InputError> {
+        return SlopeMap::new(&PuzzleInput::from_text(text).content_lines().to_vec());
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::InputError;
+    use std::convert::TryFrom;
     use super::MovementPath;
     use super::SlopeMap;
 
@@ -83,7 +128,7 @@ mod tests {
 	);
         let movement_path = MovementPath::new(3, 1);
 
-	let slope_map = SlopeMap::new(&values);
+	let slope_map = SlopeMap::new(&values)?;
 	let actual = slope_map.count_trees_on_traversal(&movement_path);
 
         assert_eq!(7, actual);
@@ -92,6 +137,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::useless_vec, clippy::needless_borrow, clippy::unnecessary_fold)]
     fn count_trees_on_multiple_traversals() -> Result<(), InputError> {
         let values = vec!(
 	    String::from("..##......."),
@@ -114,7 +160,7 @@ mod tests {
             MovementPath::new(1, 2),
         );
 
-	let slope_map = SlopeMap::new(&values);
+	let slope_map = SlopeMap::new(&values)?;
         let actual = movement_paths.iter()
             .map(|p| slope_map.count_trees_on_traversal(&p))
             .fold(1, |acc, x| acc * x);
@@ -123,4 +169,23 @@ mod tests {
 
         return Ok(());
     }
+
+    #[test]
+    fn try_from_text() {
+        let slope_map = SlopeMap::try_from("#.\n.#\n").unwrap();
+
+        assert_eq!(2, slope_map.count_trees_on_traversal(&MovementPath::new(1, 1)));
+        assert_eq!(Some(2), SlopeMap::try_from(".#\n#o\n").err().and_then(|e| e.column()));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip_as_rows() {
+        let json = serde_json::to_string(&SlopeMap::try_from("#.\n.#\n").unwrap()).unwrap();
+        assert_eq!(r##"{"rows":["#.",".#"]}"##, json);
+
+        let restored: SlopeMap = serde_json::from_str(&json).unwrap();
+        assert_eq!(2, restored.count_trees_on_traversal(&MovementPath::new(1, 1)));
+        assert!(serde_json::from_str::<SlopeMap>(r##"{"rows":["#.","#"]}"##).is_err());
+    }
 }